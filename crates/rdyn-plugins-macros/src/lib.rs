@@ -1,24 +1,316 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote, Attribute, DeriveInput, Expr, Lit, LitStr, Meta, NestedMeta,
+    Token,
+};
 
-use rdyn_plugins::CREATE_RDYN_SYM_NAME;
+use rdyn_plugins::{
+    RDynAbi, ABI_RDYN_SYM_NAME, BUILD_PROFILE_RDYN_SYM_NAME, CREATE_RDYN_SYM_NAME,
+    INSTALL_HOST_CONTEXT_SYM_NAME, LAYOUT_RDYN_SYM_NAME, VERSION_RDYN_SYM_NAME,
+};
+
+/// Options for the RDynPlugin derive, set with `#[rdyn_plugin(...)]` attributes.
+#[derive(Default)]
+struct RDynPluginArgs {
+    /// The cfg predicate the exported symbols are gated behind.
+    export_cfg: Option<Meta>,
+    /// The ABI of the generated create plugin function.
+    abi: RDynAbi,
+    /// Whether to export the plugin's version from its `RDynVersioned` implementation.
+    versioned: bool,
+    /// Whether to export the symbol receiving the host's `HostContext`.
+    host_context: bool,
+}
+
+impl RDynPluginArgs {
+    fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+
+        for attr in attrs.iter().filter(|a| a.path.is_ident("rdyn_plugin")) {
+            let options = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "expected options, e.g. #[rdyn_plugin(export_cfg = \"...\")]",
+                    ))
+                }
+            };
+
+            for option in options {
+                match option {
+                    NestedMeta::Meta(Meta::NameValue(option))
+                        if option.path.is_ident("export_cfg") =>
+                    {
+                        args.export_cfg = Some(match option.lit {
+                            Lit::Str(predicate) => predicate.parse()?,
+                            lit => {
+                                return Err(syn::Error::new_spanned(
+                                    lit,
+                                    "expected a cfg predicate string, e.g. \"feature = \\\"rdyn-export\\\"\"",
+                                ))
+                            }
+                        });
+                    }
+                    NestedMeta::Meta(Meta::NameValue(option)) if option.path.is_ident("abi") => {
+                        args.abi = match &option.lit {
+                            Lit::Str(name) => {
+                                RDynAbi::from_name(&name.value()).ok_or_else(|| {
+                                    syn::Error::new_spanned(
+                                        name,
+                                        "unsupported ABI, expected \"Rust\" or \"C\"",
+                                    )
+                                })?
+                            }
+                            lit => {
+                                return Err(syn::Error::new_spanned(
+                                    lit,
+                                    "expected an ABI string, e.g. \"C\"",
+                                ))
+                            }
+                        };
+                    }
+                    NestedMeta::Meta(Meta::Path(option)) if option.is_ident("versioned") => {
+                        args.versioned = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(option)) if option.is_ident("host_context") => {
+                        args.host_context = true;
+                    }
+                    option => {
+                        return Err(syn::Error::new_spanned(
+                            option,
+                            "unknown rdyn_plugin option",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
 
 /// Macro derive for structs implementing the bevy Plugin trait
 /// that marks the plugin as the main or "entry" plugin for the dynamic plugin.
-#[proc_macro_derive(RDynPlugin)]
+///
+/// # Options
+/// - `#[rdyn_plugin(export_cfg = "...")]` only exports the plugin's symbols when the given cfg predicate holds.
+///   Defaults to `not(test)`, so that the unmangled symbols do not conflict when the plugin crate is
+///   linked into tests or another binary. For example `export_cfg = "feature = \"rdyn-export\""`
+///   only exports the symbols when the plugin crate's "rdyn-export" feature is enabled.
+/// - `#[rdyn_plugin(abi = "C")]` declares the create plugin function with the C ABI instead of the Rust ABI,
+///   for experimenting with the calling convention. The host reads the ABI from the exported ABI symbol.
+/// - `#[rdyn_plugin(versioned)]` also exports the plugin's version from its `RDynVersioned` implementation,
+///   so that the host can read it when the plugin is loaded.
+/// - `#[rdyn_plugin(host_context)]` also exports the symbol receiving the host's `HostContext` before
+///   the plugin is created, required by plugins using `HostAllocator` or the host's tokio runtime.
+///   The plugin crate must depend on `rdyn_plugins` directly, as the symbol refers to `::rdyn_plugins::HostContext`.
+#[proc_macro_derive(RDynPlugin, attributes(rdyn_plugin))]
 pub fn rdyn_plugin_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
+    let args = match RDynPluginArgs::from_attributes(&ast.attrs) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     let struct_name = &ast.ident;
     let func_name = syn::Ident::new(
         std::str::from_utf8(CREATE_RDYN_SYM_NAME).unwrap(),
         struct_name.span(),
     );
+    let build_profile_name = syn::Ident::new(
+        std::str::from_utf8(BUILD_PROFILE_RDYN_SYM_NAME).unwrap(),
+        struct_name.span(),
+    );
+    let layout_name = syn::Ident::new(
+        std::str::from_utf8(LAYOUT_RDYN_SYM_NAME).unwrap(),
+        struct_name.span(),
+    );
+    let abi_sym_name = syn::Ident::new(
+        std::str::from_utf8(ABI_RDYN_SYM_NAME).unwrap(),
+        struct_name.span(),
+    );
+    let abi = args.abi.name();
+    let export_cfg = args.export_cfg.unwrap_or_else(|| parse_quote!(not(test)));
+    let install_host_context = args.host_context.then(|| {
+        let install_host_context_name = syn::Ident::new(
+            std::str::from_utf8(INSTALL_HOST_CONTEXT_SYM_NAME).unwrap(),
+            struct_name.span(),
+        );
+        quote! {
+            #[cfg(#export_cfg)]
+            #[no_mangle]
+            pub extern "Rust" fn #install_host_context_name(
+                host_context: &::rdyn_plugins::HostContext,
+            ) {
+                ::rdyn_plugins::HostContext::install(host_context);
+            }
+        }
+    });
+    let version = args.versioned.then(|| {
+        let version_name = syn::Ident::new(
+            std::str::from_utf8(VERSION_RDYN_SYM_NAME).unwrap(),
+            struct_name.span(),
+        );
+        quote! {
+            #[cfg(#export_cfg)]
+            #[no_mangle]
+            pub extern "Rust" fn #version_name() -> &'static str {
+                RDynVersioned::version(&#struct_name {})
+            }
+        }
+    });
+
+    // Spanned to the struct name so that a missing `impl Plugin` is reported once against the deriving type,
+    // from the create function when it is exported and from an otherwise unused closure when it is not.
+    let create_plugin = quote_spanned! {struct_name.span()=>
+        {
+            fn assert_plugin<T: Plugin>(plugin: T) -> RDynReturn {
+                Box::new(plugin)
+            }
+            assert_plugin(#struct_name {})
+        }
+    };
 
     TokenStream::from(quote! {
+        #[cfg(not(#export_cfg))]
+        const _: fn() -> RDynReturn = || #create_plugin;
+
+        #[cfg(#export_cfg)]
+        #[no_mangle]
+        #[allow(improper_ctypes_definitions)]
+        pub extern #abi fn #func_name() -> RDynReturn #create_plugin
+
+        #[cfg(#export_cfg)]
         #[no_mangle]
-        pub extern "Rust" fn #func_name() -> RDynReturn {
-            Box::new(#struct_name {})
+        pub extern "Rust" fn #abi_sym_name() -> &'static str {
+            #abi
+        }
+
+        #[cfg(#export_cfg)]
+        #[no_mangle]
+        pub extern "Rust" fn #build_profile_name() -> &'static str {
+            if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+        }
+
+        #[cfg(#export_cfg)]
+        #[no_mangle]
+        pub extern "Rust" fn #layout_name() -> RDynReturnLayout {
+            RDynReturnLayout::HOST
+        }
+
+        #install_host_context
+
+        #version
+    })
+}
+
+/// Arguments of the load_embedded_mods macro: the app to load into and the directory to embed.
+struct EmbeddedModsArgs {
+    app: Expr,
+    directory: LitStr,
+}
+
+impl Parse for EmbeddedModsArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let app = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let directory = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { app, directory })
+    }
+}
+
+/// Embed every mod library in a directory into the executable at compile time, and load them from memory
+/// into an app with its "load_embedded_mods" extension method, for single file distribution.
+/// The directory is relative to the invoking crate's `Cargo.toml`.
+///
+/// Each library is embedded with `include_bytes!` and loaded in order of file name.
+/// Only libraries for the target platform are embedded, `.dll` files on Windows, `.dylib` files on macOS
+/// and `.so` files elsewhere, so one directory can hold the mods for every platform.
+///
+/// # Limitations
+/// - Every embedded library adds its full size to the executable and to its memory use,
+///   on top of the memory the loaded library is mapped into.
+/// - The directory is read when the macro expands. Changing an embedded library triggers a rebuild,
+///   but adding or removing one does not until the invoking crate is rebuilt for another reason.
+/// - See "load_mod_from_bytes" for how libraries are loaded from memory on each platform.
+///
+/// # Example
+/// ```ignore
+/// let mut app = App::new();
+/// load_embedded_mods!(&mut app, "mods");
+/// ```
+#[proc_macro]
+pub fn load_embedded_mods(input: TokenStream) -> TokenStream {
+    let EmbeddedModsArgs { app, directory } = parse_macro_input!(input as EmbeddedModsArgs);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let mods_directory = std::path::Path::new(&manifest_dir).join(directory.value());
+    let mut mod_paths: Vec<std::path::PathBuf> = match std::fs::read_dir(&mods_directory) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|f| f.is_file()))
+            .map(|entry| entry.path())
+            .collect(),
+        Err(err) => {
+            return syn::Error::new_spanned(
+                &directory,
+                format!(
+                    "could not read mods directory '{}': {}",
+                    mods_directory.display(),
+                    err
+                ),
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    mod_paths.sort();
+
+    let mut pushes = Vec::new();
+    for mod_path in mod_paths {
+        let platform = match mod_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("dll") => quote!(windows),
+            Some("dylib") => quote!(target_os = "macos"),
+            Some("so") => quote!(not(any(windows, target_os = "macos"))),
+            _ => continue,
+        };
+        let (Some(path), Some(file_name)) = (
+            mod_path.to_str(),
+            mod_path
+                .file_name()
+                .and_then(|file_name| file_name.to_str()),
+        ) else {
+            return syn::Error::new_spanned(
+                &directory,
+                format!("mod path '{}' is not valid unicode", mod_path.display()),
+            )
+            .to_compile_error()
+            .into();
+        };
+        let name = format!("{}/{}", directory.value(), file_name);
+        pushes.push(quote! {
+            #[cfg(#platform)]
+            mods.push((#name, include_bytes!(#path)));
+        });
+    }
+
+    TokenStream::from(quote! {
+        {
+            #[allow(unused_mut)]
+            let mut mods: Vec<(&str, &[u8])> = Vec::new();
+            #(#pushes)*
+            ModLoaderExt::load_embedded_mods(#app, &mods)
         }
     })
 }
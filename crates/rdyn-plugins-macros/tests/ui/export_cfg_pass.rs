@@ -0,0 +1,62 @@
+//! The RDynPlugin derive exports nothing when its `export_cfg` predicate does not hold,
+//! so defining the exported symbols here does not clash with the derive.
+#![allow(unexpected_cfgs)]
+
+use bevy::prelude::*;
+use rdyn_plugins::*;
+use rdyn_plugins_macros::RDynPlugin;
+
+#[derive(RDynPlugin)]
+#[rdyn_plugin(versioned, host_context, export_cfg = "feature = \"rdyn-export\"")]
+pub struct TestPlugin;
+
+impl Plugin for TestPlugin {
+    fn build(&self, _app: &mut App) {}
+}
+
+impl RDynVersioned for TestPlugin {}
+
+struct OwnPlugin;
+
+impl Plugin for OwnPlugin {
+    fn build(&self, _app: &mut App) {}
+    fn name(&self) -> &str {
+        "own"
+    }
+}
+
+#[no_mangle]
+pub extern "Rust" fn _create_rdyn_plugin() -> RDynReturn {
+    Box::new(OwnPlugin)
+}
+
+#[no_mangle]
+pub extern "Rust" fn _rdyn_abi() -> &'static str {
+    "own"
+}
+
+#[no_mangle]
+pub extern "Rust" fn _rdyn_install_host_context(_host_context: &HostContext) {}
+
+#[no_mangle]
+pub extern "Rust" fn _rdyn_build_profile() -> &'static str {
+    "own"
+}
+
+#[no_mangle]
+pub extern "Rust" fn _rdyn_layout() -> RDynReturnLayout {
+    RDynReturnLayout { size: 0, align: 0 }
+}
+
+#[no_mangle]
+pub extern "Rust" fn _rdyn_version() -> &'static str {
+    "own"
+}
+
+fn main() {
+    assert_eq!(_create_rdyn_plugin().name(), "own");
+    assert_eq!(_rdyn_abi(), "own");
+    assert_eq!(_rdyn_build_profile(), "own");
+    assert_eq!(_rdyn_layout().size, 0);
+    assert_eq!(_rdyn_version(), "own");
+}
@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+use rdyn_plugins::*;
+use rdyn_plugins_macros::RDynPlugin;
+
+#[derive(RDynPlugin)]
+pub struct NotAPlugin;
+
+fn main() {}
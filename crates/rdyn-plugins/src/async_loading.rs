@@ -0,0 +1,271 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    path::PathBuf,
+    sync::{mpsc, Mutex},
+};
+
+use bevy::{
+    ecs::{
+        event::Events,
+        schedule::{Schedule, SystemStage},
+    },
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, TaskPool},
+};
+
+use crate::{dyn_api::*, error::*, host_context::*, mod_loader::*};
+
+/// A mod whose library was opened and plugin created off the main thread, ready to be built.
+struct OpenedMod {
+    path: PathBuf,
+    priority: i32,
+    result: Result<RustDynPlugin, ModLoadError>,
+}
+
+/// Sends the mod opened by a task to its [LoadModsTask] when dropped, with [ModLoadError::OpenTaskPanicked]
+/// if the task panicked before the mod was opened, so that the [LoadModsTask] never waits on a mod forever.
+struct OpenedModSender {
+    sender: mpsc::Sender<OpenedMod>,
+    path: PathBuf,
+    priority: i32,
+    result: Option<Result<RustDynPlugin, ModLoadError>>,
+}
+
+impl Drop for OpenedModSender {
+    fn drop(&mut self) {
+        let result = self
+            .result
+            .take()
+            .unwrap_or(Err(ModLoadError::OpenTaskPanicked));
+        // The receiver is only gone if the task was abandoned, in which case the mod is dropped.
+        let _ = self.sender.send(OpenedMod {
+            path: std::mem::take(&mut self.path),
+            priority: self.priority,
+            result,
+        });
+    }
+}
+
+/// Tracks the mods being loaded by the "load_mods_async" extension method.
+///
+/// Each mod's library is opened and its plugin created on the [AsyncComputeTaskPool].
+/// Once all have been opened, [finish_async_mod_loads] builds them on the main thread in order of their
+/// load priority, as by "load_mods", stores them in the [ModLoaderData] resource and removes this resource.
+pub struct LoadModsTask {
+    receiver: Mutex<mpsc::Receiver<OpenedMod>>,
+    opened: Vec<OpenedMod>,
+    total: usize,
+    sources: Vec<ModSource>,
+}
+
+impl LoadModsTask {
+    /// The number of mods opened so far.
+    #[inline]
+    pub fn opened(&self) -> usize {
+        self.opened.len()
+    }
+
+    /// The number of mods being loaded.
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The fraction of mods opened so far, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        match self.total {
+            0 => 1.0,
+            total => self.opened.len() as f32 / total as f32,
+        }
+    }
+}
+
+/// Event sent by [finish_async_mod_loads] whenever more mods have been opened, for driving a loading bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModLoadProgress {
+    /// The number of mods opened so far.
+    pub opened: usize,
+    /// The number of mods being loaded.
+    pub total: usize,
+}
+
+/// Sets up the [ModLoadProgress] event and the [finish_async_mod_loads] system,
+/// required for mods loaded via the "load_mods_async" extension method to be built.
+pub struct AsyncModLoadingPlugin;
+
+impl Plugin for AsyncModLoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ModLoadProgress>()
+            .add_system(finish_async_mod_loads.exclusive_system());
+    }
+}
+
+/// Start opening all mods found in a directory on the [AsyncComputeTaskPool],
+/// tracked by a [LoadModsTask] resource.
+pub(crate) fn start_load_mods_async(app: &mut App, mods_directory: &str) {
+    let mod_paths: Vec<PathBuf> = scan_mods_directory(mods_directory)
+        .unwrap_or_else(|err| {
+            warn_unreadable_mods_directory(mods_directory, &err);
+            Vec::new()
+        })
+        .into_iter()
+        .filter(|mod_path| match mod_path.to_str() {
+            Some(_) => true,
+            None => {
+                warn!(target: LOG_TARGET, "Failed to get path of plugin from: '{:?}'", mod_path);
+                false
+            }
+        })
+        .collect();
+
+    let loader = plugin_loader(app);
+    let host_context = app.world.get_resource::<HostContext>().cloned();
+    let (sender, receiver) = mpsc::channel();
+    let task_pool = app
+        .world
+        .get_resource_or_insert_with(|| AsyncComputeTaskPool(TaskPool::new()));
+
+    for mod_path in mod_paths.iter().cloned() {
+        let loader = loader.clone();
+        let host_context = host_context.clone();
+        let sender = sender.clone();
+        task_pool
+            .spawn(async move {
+                let mut opened = OpenedModSender {
+                    sender,
+                    path: mod_path,
+                    priority: DEFAULT_RDYN_PRIORITY,
+                    result: None,
+                };
+                // Paths that are not valid unicode were filtered out above.
+                let path = opened.path.to_str().unwrap_or_default().to_owned();
+                opened.priority = loader.priority(&path);
+                opened.result = Some(loader.load(&path, host_context.as_ref()));
+            })
+            .detach();
+    }
+
+    app.insert_resource(LoadModsTask {
+        receiver: Mutex::new(receiver),
+        opened: Vec::new(),
+        total: mod_paths.len(),
+        sources: vec![ModSource::Directory(mods_directory.to_string())],
+    });
+}
+
+/// Exclusive system that collects the mods opened by a [LoadModsTask], sending [ModLoadProgress] events,
+/// and once all have been opened builds them and stores them in the [ModLoaderData] resource.
+///
+/// Plugins are built into the running app's world, but its schedule cannot be accessed from a system.
+/// Mods loaded asynchronously must therefore add their systems with "add_plugin_system",
+/// systems added to the schedule are discarded and a warning is logged.
+pub fn finish_async_mod_loads(world: &mut World) {
+    let mut task = match world.remove_resource::<LoadModsTask>() {
+        Some(task) => task,
+        None => return,
+    };
+
+    let opened_before = task.opened.len();
+    if let Ok(receiver) = task.receiver.get_mut() {
+        task.opened.extend(receiver.try_iter());
+    }
+    if task.opened.len() != opened_before {
+        if let Some(mut progress_events) = world.get_resource_mut::<Events<ModLoadProgress>>() {
+            progress_events.send(ModLoadProgress {
+                opened: task.opened.len(),
+                total: task.total,
+            });
+        }
+    }
+    if task.opened.len() < task.total {
+        world.insert_resource(task);
+        return;
+    }
+
+    task.opened
+        .sort_by_cached_key(|opened_mod| (Reverse(opened_mod.priority), opened_mod.path.clone()));
+    let mod_paths: Vec<PathBuf> = task
+        .opened
+        .iter()
+        .map(|opened_mod| opened_mod.path.clone())
+        .collect();
+    let mut results: HashMap<PathBuf, Result<RustDynPlugin, ModLoadError>> = task
+        .opened
+        .into_iter()
+        .map(|opened_mod| (opened_mod.path, opened_mod.result))
+        .collect();
+
+    // Plugins can only be built into an app, so the world is lent to a temporary one while building.
+    let mut app = App::new();
+    std::mem::swap(&mut app.world, world);
+    let systems_before = count_schedule_systems(&app.schedule);
+    let open = |_: &mut App, mod_path: &str| {
+        results
+            .remove(std::path::Path::new(mod_path))
+            .expect("every mod path has been opened")
+    };
+    load_sorted_mods_with::<()>(&mut app, &mod_paths, task.sources, open, |_, _| {});
+    if count_schedule_systems(&app.schedule) != systems_before {
+        warn!(
+            target: LOG_TARGET,
+            "Mods loaded asynchronously added systems to the schedule, which cannot be added to a running app \
+             and have been discarded. Add them with \"add_plugin_system\" instead."
+        );
+    }
+    std::mem::swap(&mut app.world, world);
+}
+
+/// The number of systems in every stage of a schedule, including nested schedules.
+pub(crate) fn count_schedule_systems(schedule: &Schedule) -> usize {
+    schedule
+        .iter_stages()
+        .map(|(_, stage)| {
+            if let Some(stage) = stage.downcast_ref::<SystemStage>() {
+                stage.parallel_systems().len()
+                    + stage.exclusive_at_start_systems().len()
+                    + stage.exclusive_before_commands_systems().len()
+                    + stage.exclusive_at_end_systems().len()
+            } else if let Some(schedule) = stage.downcast_ref::<Schedule>() {
+                count_schedule_systems(schedule)
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin_loader::*;
+
+    #[test]
+    fn panicking_open_task_is_recorded_as_failure() {
+        let mods_directory =
+            std::env::temp_dir().join(format!("rdyn_async_panic_{}", std::process::id()));
+        std::fs::create_dir_all(&mods_directory).unwrap();
+        let mod_path = mods_directory.join("panicking.dll");
+        std::fs::write(&mod_path, []).unwrap();
+        let loader =
+            MockLoader::new().with_plugin(&mod_path, || panic!("create function panicked"));
+
+        let mut app = App::new();
+        app.add_plugin(AsyncModLoadingPlugin)
+            .insert_resource(ModPluginLoader::new(loader))
+            .load_mods_async(mods_directory.to_str().unwrap());
+        while app.world.contains_resource::<LoadModsTask>() {
+            finish_async_mod_loads(&mut app.world);
+            std::thread::yield_now();
+        }
+
+        let mod_loader_data = app.world.resource::<ModLoaderData>();
+        assert!(mod_loader_data.is_empty());
+        assert!(matches!(
+            mod_loader_data.failures(),
+            [(path, ModLoadError::OpenTaskPanicked)] if *path == mod_path
+        ));
+
+        std::fs::remove_dir_all(mods_directory).unwrap();
+    }
+}
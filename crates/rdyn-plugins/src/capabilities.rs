@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+
+use libloading::{Library, Symbol};
+
+use crate::{dyn_api::*, error::*};
+
+/// Name of the optional symbol exported by a plugin to declare the capabilities it needs,
+/// such as `"filesystem"`, `"network"` or `"threads"`. Plugins without the symbol declare none.
+/// Declarations are not enforced, a plugin can do anything the host can, but they allow a host
+/// to refuse mods or ask the user for consent before loading them, see [CapabilityPolicy].
+/// # Example
+/// ```
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_capabilities() -> Vec<String> {
+///     vec!["filesystem".to_string(), "network".to_string()]
+/// }
+/// ```
+pub const CAPABILITIES_RDYN_SYM_NAME: &[u8] = b"_rdyn_capabilities";
+/// Type that represents the function signature of the capabilities symbol.
+pub type RDynCapabilities = fn() -> Vec<String>;
+
+/// The capabilities a host grants to mods, checked against each mod's declared capabilities before it is built.
+/// Mods declaring a capability that is not granted are skipped with [ModLoadError::CapabilityDenied].
+#[derive(Debug, Default, Clone)]
+pub enum CapabilityPolicy {
+    /// Grant every capability.
+    #[default]
+    AllowAll,
+    /// Grant only the listed capabilities.
+    Allow(HashSet<String>),
+    /// Grant every capability except the listed ones.
+    Deny(HashSet<String>),
+}
+
+impl CapabilityPolicy {
+    /// Whether the capability is granted by this policy.
+    pub fn grants(&self, capability: &str) -> bool {
+        match self {
+            CapabilityPolicy::AllowAll => true,
+            CapabilityPolicy::Allow(allowed) => allowed.contains(capability),
+            CapabilityPolicy::Deny(denied) => !denied.contains(capability),
+        }
+    }
+
+    /// Check the capabilities declared by a mod,
+    /// returning [ModLoadError::CapabilityDenied] listing every capability that is not granted.
+    pub fn check(&self, capabilities: &[String]) -> Result<(), ModLoadError> {
+        let denied: Vec<String> = capabilities
+            .iter()
+            .filter(|capability| !self.grants(capability))
+            .cloned()
+            .collect();
+        match denied.is_empty() {
+            true => Ok(()),
+            false => Err(ModLoadError::CapabilityDenied { denied }),
+        }
+    }
+}
+
+/// Read the capabilities declared by a rust dynamic plugin from the specified path without creating the plugin,
+/// such as to show them to the user before loading the mod.
+/// # Unsafety
+/// Undefined behaviour expected if the symbol loaded from [CAPABILITIES_RDYN_SYM_NAME]
+/// does not match the function signature [RDynCapabilities]
+pub fn peek_rdyn_capabilities(path: &str) -> Result<Vec<String>, ModLoadError> {
+    let loaded_library = load_rdyn_library(path)?;
+    Ok(read_rdyn_capabilities(&loaded_library.library))
+}
+
+/// Read the capabilities declared by an already loaded library, none if it does not export the symbol.
+pub(crate) fn read_rdyn_capabilities(library: &Library) -> Vec<String> {
+    let capabilities_sym: Option<Symbol<RDynCapabilities>> =
+        unsafe { library.get(CAPABILITIES_RDYN_SYM_NAME) }.ok();
+    capabilities_sym
+        .map(|capabilities_sym| capabilities_sym())
+        .unwrap_or_default()
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+use crate::{async_loading::*, dyn_api::*, error::*, mod_loader::*};
+
+/// A plugin that has been created but not yet built, so that the host can choose when to build it,
+/// such as after the user enables the mod in a menu.
+///
+/// How the plugin can set itself up depends on when it is built:
+/// - With [DeferredPlugin::build_now] while the app is still being constructed, before it is run.
+///   The plugin is built as by "load_mod" and can do anything a plugin normally can, including `add_plugin`.
+/// - With [DeferredPlugin::build_in_world] once the app is running, such as from an exclusive system.
+///   The schedule of a running app cannot be accessed, so only the plugin's resources and the systems it adds
+///   with "add_plugin_system" take effect. If the plugin, or a sub-plugin it adds with `add_plugin`,
+///   adds systems to the schedule, they are discarded and [ModLoadError::ScheduleModifiedWhileRunning] is returned
+///   rather than silently ignoring them.
+///
+/// Either way the plugin can only be built once, building it again returns [ModLoadError::AlreadyBuilt].
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// struct SchedulePlugin;
+///
+/// impl Plugin for SchedulePlugin {
+///     fn build(&self, app: &mut App) {
+///         app.add_system(|| {});
+///     }
+/// }
+///
+/// let plugin = RustDynPlugin::from_static(Box::new(SchedulePlugin), "schedule").unwrap();
+/// let mut deferred = DeferredPlugin::new(plugin);
+///
+/// let mut app = App::new();
+/// assert!(matches!(
+///     deferred.build_in_world(&mut app.world),
+///     Err(ModLoadError::ScheduleModifiedWhileRunning { discarded_systems: 1 })
+/// ));
+/// assert!(deferred.is_built());
+/// assert!(matches!(
+///     deferred.build_now(&mut app),
+///     Err(ModLoadError::AlreadyBuilt)
+/// ));
+/// ```
+pub struct DeferredPlugin {
+    plugin: RustDynPlugin,
+    built: bool,
+}
+
+impl DeferredPlugin {
+    /// Wrap a created plugin to be built later.
+    pub fn new(plugin: RustDynPlugin) -> Self {
+        Self {
+            plugin,
+            built: false,
+        }
+    }
+
+    /// Whether the plugin has been built, regardless of whether its build succeeded.
+    #[inline]
+    pub fn is_built(&self) -> bool {
+        self.built
+    }
+
+    /// The wrapped plugin.
+    #[inline]
+    pub fn plugin(&self) -> &RustDynPlugin {
+        &self.plugin
+    }
+
+    /// Unwrap the plugin, such as to store it in the [ModLoaderData] resource once it has been built.
+    #[inline]
+    pub fn into_inner(self) -> RustDynPlugin {
+        self.plugin
+    }
+
+    /// Build the plugin into an app that is still being constructed, checking it against the app's
+    /// [ModLoaderSettings] as by "load_mod". A plugin that is skipped by the settings is left unbuilt.
+    pub fn build_now(&mut self, app: &mut App) -> Result<(), ModLoadError> {
+        if self.built {
+            return Err(ModLoadError::AlreadyBuilt);
+        }
+        check_loaded_mod(app, &self.plugin)?;
+        build_checked_mod(app, &mut self.plugin);
+        self.built = true;
+        Ok(())
+    }
+
+    /// Build the plugin into the world of a running app, see [DeferredPlugin] for what the plugin can do.
+    /// Returns [ModLoadError::ScheduleModifiedWhileRunning] if the plugin added systems to the schedule,
+    /// in which case the plugin is still built and everything but those systems is in place.
+    pub fn build_in_world(&mut self, world: &mut World) -> Result<(), ModLoadError> {
+        // Plugins can only be built into an app, so the world is lent to a temporary one while building.
+        let mut app = App::new();
+        std::mem::swap(&mut app.world, world);
+        let systems_before = count_schedule_systems(&app.schedule);
+        let result = self.build_now(&mut app);
+        let discarded_systems = count_schedule_systems(&app.schedule) - systems_before;
+        std::mem::swap(&mut app.world, world);
+
+        result?;
+        match discarded_systems {
+            0 => Ok(()),
+            discarded_systems => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Mod '{}' added {} systems to the schedule while being built into a running app, \
+                     which have been discarded. Add them with \"add_plugin_system\" instead.",
+                    self.plugin.name(),
+                    discarded_systems
+                );
+                Err(ModLoadError::ScheduleModifiedWhileRunning { discarded_systems })
+            }
+        }
+    }
+}
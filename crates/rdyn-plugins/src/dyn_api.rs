@@ -1,25 +1,331 @@
 use std::{
+    ffi::c_void,
     fmt::Debug,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-use bevy::prelude::Plugin;
+#[cfg(feature = "trace")]
+use bevy::log::info_span;
+use bevy::prelude::{Plugin, World};
 use libloading::{Library, Symbol};
 
+use crate::{capabilities::*, error::*, flags::*, host_context::*, platform::*, version::*};
+
 /// Name of symbol to be exported/imported to create the plugin.
-pub const CREATE_RDYN_SYM_NAME: &'static [u8] = b"_create_rdyn_plugin";
+pub const CREATE_RDYN_SYM_NAME: &[u8] = b"_create_rdyn_plugin";
 /// The type required to be returned from the plugin creation function.
 pub type RDynReturn = Box<dyn Plugin>;
-/// Type that represents the function signature of create plugin symbol.
-pub type CreateRDynPlugin = fn() -> RDynReturn;
+/// Name of the symbol exported by plugins to report the [RDynAbi] of their create plugin symbol,
+/// as its name such as `"Rust"`. This symbol is generated by the RDynPlugin derive,
+/// plugins that do not export it are assumed to use [RDynAbi::Rust].
+pub const ABI_RDYN_SYM_NAME: &[u8] = b"_rdyn_abi";
+/// Type that represents the function signature of the ABI symbol.
+pub type RDynAbiName = fn() -> &'static str;
+
+/// Defines [RDynAbi] together with the create plugin symbol type of each ABI, expanding each ABI's name
+/// both into the `extern "..."` of its symbol type and into [RDynAbi::name], which the RDynPlugin derive uses,
+/// so that the two cannot drift.
+macro_rules! rdyn_abis {
+    (
+        $(#[$attr:meta])*
+        pub enum RDynAbi {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $abi:tt => $create_fn:ident,
+            )*
+        }
+    ) => {
+        $(
+            #[doc = concat!(
+                "Type that represents the function signature of create plugin symbol, for plugins using [RDynAbi::",
+                stringify!($variant),
+                "]."
+            )]
+            #[allow(improper_ctypes_definitions)]
+            pub type $create_fn = extern $abi fn() -> RDynReturn;
+        )*
+
+        $(#[$attr])*
+        pub enum RDynAbi {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )*
+        }
+
+        impl RDynAbi {
+            /// The name of the ABI, as written in `extern "..."`.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(RDynAbi::$variant => $abi,)*
+                }
+            }
+
+            /// The ABI with the specified name, `None` if it is not supported.
+            /// # Example
+            /// ```
+            /// # use rdyn_plugins::*;
+            /// assert_eq!(RDynAbi::from_name(RDynAbi::C.name()), Some(RDynAbi::C));
+            /// assert_eq!(RDynAbi::from_name("stdcall"), None);
+            /// ```
+            pub fn from_name(name: &str) -> Option<Self> {
+                [$(RDynAbi::$variant),*]
+                    .into_iter()
+                    .find(|abi| abi.name() == name)
+            }
+        }
+
+        /// A library's create plugin symbol, called with its [RDynAbi].
+        #[derive(Clone, Copy)]
+        enum CreatePluginFn {
+            $($variant($create_fn),)*
+        }
+
+        impl CreatePluginFn {
+            /// Get the create plugin symbol of a library using the specified ABI.
+            fn get(library: &Library, abi: RDynAbi) -> Result<Self, ModLoadError> {
+                match abi {
+                    $(RDynAbi::$variant => {
+                        let create_plugin_sym: Symbol<$create_fn> =
+                            unsafe { library.get(CREATE_RDYN_SYM_NAME) }
+                                .map_err(|_| ModLoadError::MissingCreateSymbol)?;
+                        Ok(CreatePluginFn::$variant(*create_plugin_sym))
+                    })*
+                }
+            }
+
+            fn call(self) -> RDynReturn {
+                match self {
+                    $(CreatePluginFn::$variant(create_plugin) => create_plugin(),)*
+                }
+            }
+        }
+    };
+}
+
+rdyn_abis! {
+    /// The calling convention of a plugin's [create plugin symbol](CREATE_RDYN_SYM_NAME).
+    /// This is the single definition shared by the host, which calls the symbol as [CreateRDynPlugin] or
+    /// [CreateRDynPluginC] accordingly, and the RDynPlugin derive, which declares the symbol with [RDynAbi::name].
+    ///
+    /// Plugins use [RDynAbi::Rust] unless the derive is given the `#[rdyn_plugin(abi = "C")]` option.
+    /// The plugin is still returned as a [RDynReturn] with either ABI, so the C ABI does not make
+    /// the plugin compatible across compiler versions, it is intended for experimenting with the calling convention.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum RDynAbi {
+        #[default]
+        Rust = "Rust" => CreateRDynPlugin,
+        C = "C" => CreateRDynPluginC,
+    }
+}
+/// Name of the optional symbol exported by a plugin to declare its load priority.
+/// Plugins with a higher priority are loaded first, plugins without the symbol have a priority of 0.
+/// # Example
+/// ```
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_priority() -> i32 {
+///     10
+/// }
+/// ```
+pub const PRIORITY_RDYN_SYM_NAME: &[u8] = b"_rdyn_priority";
+/// Type that represents the function signature of the load priority symbol.
+pub type RDynPriority = fn() -> i32;
+/// The load priority of plugins that do not export the [load priority symbol](PRIORITY_RDYN_SYM_NAME).
+pub const DEFAULT_RDYN_PRIORITY: i32 = 0;
+/// Name of the optional symbol exported by a plugin to create an object implementing a host defined interface.
+/// # Example
+/// ```ignore
+/// #[no_mangle]
+/// pub extern "Rust" fn _create_rdyn_interface() -> Box<dyn SettingsPanel> {
+///     Box::new(ExampleSettingsPanel)
+/// }
+/// ```
+pub const CREATE_INTERFACE_RDYN_SYM_NAME: &[u8] = b"_create_rdyn_interface";
+/// Type that represents the function signature of the create interface symbol for the interface `T`.
+pub type CreateRDynInterface<T> = fn() -> Box<T>;
+/// Name of symbol exported by plugins to report the build profile they were compiled with,
+/// `"debug"` or `"release"`. This symbol is generated by the RDynPlugin derive.
+pub const BUILD_PROFILE_RDYN_SYM_NAME: &[u8] = b"_rdyn_build_profile";
+/// Type that represents the function signature of the build profile symbol.
+pub type RDynBuildProfile = fn() -> &'static str;
+/// The build profile the host was compiled with, as reported by the [build profile symbol](BUILD_PROFILE_RDYN_SYM_NAME).
+pub const HOST_BUILD_PROFILE: &str = if cfg!(debug_assertions) {
+    "debug"
+} else {
+    "release"
+};
+
+/// Name of symbol exported by plugins to report the layout of [RDynReturn] they were compiled with,
+/// checked against the host's before the plugin is created. This symbol is generated by the RDynPlugin derive.
+pub const LAYOUT_RDYN_SYM_NAME: &[u8] = b"_rdyn_layout";
+/// Type that represents the function signature of the layout symbol.
+pub type RDynLayout = fn() -> RDynReturnLayout;
+
+/// The size and alignment of [RDynReturn], which must be the same for the host and a plugin
+/// for the plugin to be created safely. They can differ if the two were built with different versions of rustc or bevy.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// assert!(RDynReturnLayout::HOST.check().is_ok());
+///
+/// let wrong_layout = RDynReturnLayout {
+///     size: RDynReturnLayout::HOST.size * 2,
+///     ..RDynReturnLayout::HOST
+/// };
+/// assert!(matches!(
+///     wrong_layout.check(),
+///     Err(ModLoadError::LayoutMismatch { .. })
+/// ));
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RDynReturnLayout {
+    pub size: usize,
+    pub align: usize,
+}
+
+impl RDynReturnLayout {
+    /// The layout of [RDynReturn] in the crate being compiled.
+    pub const HOST: Self = Self {
+        size: std::mem::size_of::<RDynReturn>(),
+        align: std::mem::align_of::<RDynReturn>(),
+    };
+
+    /// Check that a plugin's layout matches the host's, returning [ModLoadError::LayoutMismatch] if not.
+    pub fn check(self) -> Result<(), ModLoadError> {
+        match self == Self::HOST {
+            true => Ok(()),
+            false => Err(ModLoadError::LayoutMismatch {
+                expected: Self::HOST,
+                found: self,
+            }),
+        }
+    }
+}
+
+/// Name of the optional symbol exported by a plugin to report the version of the host's API it was compiled against.
+/// The version is defined by the host crate plugins depend on, independent of the rustc and bevy versions,
+/// and checked against [ModLoaderSettings::expected_host_api].
+/// # Example
+/// ```
+/// // In the host crate.
+/// pub const HOST_API_VERSION: u32 = 3;
+///
+/// // In the plugin crate.
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_host_api_version() -> u32 {
+///     HOST_API_VERSION
+/// }
+/// ```
+pub const HOST_API_VERSION_RDYN_SYM_NAME: &[u8] = b"_rdyn_host_api_version";
+/// Type that represents the function signature of the host API version symbol.
+pub type RDynHostApiVersion = fn() -> u32;
+
+/// Name of the optional symbol exported by a plugin to save its state before it is reloaded by "reload_mod".
+/// The returned bytes are passed to the [restore symbol](RESTORE_RDYN_SYM_NAME) of the reloaded plugin.
+/// The snapshot should remove the resources it saves from the world, as their types' code lives in the
+/// library being unloaded.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// struct Score(u32);
+///
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_snapshot(world: &mut World) -> Vec<u8> {
+///     world
+///         .remove_resource::<Score>()
+///         .map(|score| score.0.to_le_bytes().to_vec())
+///         .unwrap_or_default()
+/// }
+/// ```
+pub const SNAPSHOT_RDYN_SYM_NAME: &[u8] = b"_rdyn_snapshot";
+/// Type that represents the function signature of the snapshot symbol.
+pub type RDynSnapshot = fn(&mut World) -> Vec<u8>;
+/// Name of the optional symbol exported by a plugin to restore the state saved by the
+/// [snapshot symbol](SNAPSHOT_RDYN_SYM_NAME) of its previous version, called by "reload_mod" after the plugin is built.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// struct Score(u32);
+///
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_restore(world: &mut World, state: &[u8]) {
+///     if let Ok(bytes) = state.try_into() {
+///         world.insert_resource(Score(u32::from_le_bytes(bytes)));
+///     }
+/// }
+/// ```
+pub const RESTORE_RDYN_SYM_NAME: &[u8] = b"_rdyn_restore";
+/// Type that represents the function signature of the restore symbol.
+pub type RDynRestore = fn(&mut World, &[u8]);
+/// Name of the optional symbol exported by a plugin to tear itself down, called by "unload_all_mods"
+/// before the plugin is dropped. The shutdown should remove anything from the world that must not
+/// outlive the plugin, such as resources holding handles to threads or files the plugin owns.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// struct Connection;
+///
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_shutdown(world: &mut World) {
+///     world.remove_resource::<Connection>();
+/// }
+/// ```
+pub const SHUTDOWN_RDYN_SYM_NAME: &[u8] = b"_rdyn_shutdown";
+/// Type that represents the function signature of the shutdown symbol.
+pub type RDynShutdown = fn(&mut World);
+
+/// A host defined interface created by a plugin via the [create interface symbol](CREATE_INTERFACE_RDYN_SYM_NAME).
+/// Automatically dereferences to the interface and so can be used as such.
+///
+/// The interface's code lives in the plugin's library, so it borrows the [RustDynPlugin] or [LoadedLibrary]
+/// it was created from to guarantee the library stays loaded while the interface is in use.
+pub struct RDynInterface<'lib, T: ?Sized> {
+    interface: Box<T>,
+    _library: PhantomData<&'lib Library>,
+}
+
+impl<T: ?Sized> Deref for RDynInterface<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.interface
+    }
+}
+
+impl<T: ?Sized> DerefMut for RDynInterface<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.interface
+    }
+}
 
 /// Stores a Rust dynamic plugin along with the dynamic library from which it was loaded.
 /// Automatically deferences to a box of a bevy Plugin and so can be used as such.
 pub struct RustDynPlugin {
-    /// The library the plugin was loaded from.
-    pub library: Library,
     /// The plugin itself.
+    /// Declared before the library so that it is dropped first, as its code lives in the library.
     pub plugin: Box<dyn Plugin>,
+    /// The library the plugin was loaded from.
+    pub library: Library,
+    /// The path of the library the plugin was loaded from.
+    pub path: PathBuf,
+    /// Whether the plugin has been built into an application and its build returned.
+    /// A plugin whose build panicked, which is only recovered from with the "catch_panics" feature,
+    /// has its library loaded but may have only partially set itself up.
+    ///
+    /// Panics can only be caught if the host and plugins share the standard library, by building both with
+    /// `-C prefer-dynamic`. Otherwise each binary has its own copy, the host sees the plugin's panic as
+    /// a foreign exception and the process aborts.
+    pub build_succeeded: bool,
+    /// The modified time of the library file when the plugin was loaded, `None` if it could not be read.
+    /// Used by the "reload_mod" extension method to skip reloading a file that has not changed.
+    pub modified: Option<SystemTime>,
+    /// The version reported by the plugin's [version symbol](VERSION_RDYN_SYM_NAME) when it was loaded.
+    version: Option<String>,
 }
 
 impl Deref for RustDynPlugin {
@@ -40,7 +346,11 @@ impl Debug for RustDynPlugin {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RustDynPlugin")
             .field("library", &self.library)
+            .field("path", &self.path)
             .field("plugin", &self.plugin.name())
+            .field("build_succeeded", &self.build_succeeded)
+            .field("modified", &self.modified)
+            .field("version", &self.version)
             .finish()
     }
 }
@@ -54,32 +364,687 @@ impl RustDynPlugin {
     /// This method is just a ease of use wrapper for the [load_rdyn_plugin] function.
     #[inline]
     #[allow(dead_code)]
-    pub fn load_from(path: &str) -> Option<RustDynPlugin> {
+    pub fn load_from(path: &str) -> Result<RustDynPlugin, ModLoadError> {
         load_rdyn_plugin(path)
     }
 
-    /// Tell rust not to release the library when it goes out of scope.
+    /// Wrap a statically linked plugin so that it can be stored and built like a loaded one,
+    /// such as by a [PluginLoader] that creates plugins in process.
+    /// The plugin's library is a handle to the host process itself, since the plugin's code lives there.
+    pub fn from_static(
+        plugin: Box<dyn Plugin>,
+        path: impl Into<PathBuf>,
+    ) -> Result<RustDynPlugin, ModLoadError> {
+        #[cfg(unix)]
+        let library = Library::from(libloading::os::unix::Library::this());
+        #[cfg(not(unix))]
+        let library =
+            Library::new(std::env::current_exe()?).map_err(ModLoadError::LibraryOpenFailed)?;
+        let path = path.into();
+        Ok(RustDynPlugin {
+            plugin,
+            library,
+            modified: file_modified(&path),
+            path,
+            build_succeeded: false,
+            version: None,
+        })
+    }
+
+    /// Tell rust not to release the library when it goes out of scope,
+    /// returning the plugin that was loaded from it.
     /// # Use case
     /// Used when you do not want to store the library in the program,
     /// but you do not want the library to be freed.
     /// In this case you would "forget" the library to keep it loaded.
     #[inline]
     #[allow(dead_code)]
-    pub fn forget_library(&self) {
-        std::mem::forget(&self.library);
+    pub fn forget_library(self) -> Box<dyn Plugin> {
+        std::mem::forget(self.library);
+        self.plugin
+    }
+
+    /// Run a closure with access to the library the plugin was loaded from.
+    /// # Soundness
+    /// Anything produced by the library, such as symbols, functions, vtables or boxed values,
+    /// is only valid for as long as the library remains loaded.
+    /// The library is borrowed from the plugin for the duration of the closure and cannot be
+    /// dropped or forgotten until it returns, so it is guaranteed to stay loaded while the closure runs.
+    /// Values produced by the library must not be returned from the closure unless they do not
+    /// depend on the library's code or data, as they may otherwise outlive it.
+    /// # Example
+    /// ```no_run
+    /// # use rdyn_plugins::*;
+    /// let plugin = RustDynPlugin::load_from("plugins/plugin.dll").unwrap();
+    /// let priority = plugin.with_library(|library| unsafe {
+    ///     library
+    ///         .get::<RDynPriority>(PRIORITY_RDYN_SYM_NAME)
+    ///         .map(|priority| priority())
+    ///         .ok()
+    /// });
+    /// ```
+    #[inline]
+    pub fn with_library<R>(&self, f: impl FnOnce(&Library) -> R) -> R {
+        f(&self.library)
+    }
+
+    /// Create the host defined interface `T` exported by the plugin,
+    /// returns `None` if the plugin does not export the [create interface symbol](CREATE_INTERFACE_RDYN_SYM_NAME).
+    /// # Unsafety
+    /// Undefined behaviour expected if the symbol loaded from [CREATE_INTERFACE_RDYN_SYM_NAME]
+    /// does not match the function signature [CreateRDynInterface] for the interface `T`.
+    /// The interface trait is passed across the Rust ABI, so it must be defined in a crate shared by the host
+    /// and the plugin, and both must be built with the same compiler and the same version of that crate.
+    /// # Example
+    /// ```no_run
+    /// # use rdyn_plugins::*;
+    /// pub trait SettingsPanel {
+    ///     fn show(&self);
+    /// }
+    ///
+    /// let plugin = RustDynPlugin::load_from("plugins/plugin.dll").unwrap();
+    /// if let Some(panel) = plugin.interface::<dyn SettingsPanel>() {
+    ///     panel.show();
+    /// }
+    /// ```
+    pub fn interface<T: ?Sized>(&self) -> Option<RDynInterface<'_, T>> {
+        let create_interface_sym: Symbol<CreateRDynInterface<T>> =
+            unsafe { self.library.get(CREATE_INTERFACE_RDYN_SYM_NAME) }.ok()?;
+        Some(RDynInterface {
+            interface: create_interface_sym(),
+            _library: PhantomData,
+        })
+    }
+
+    /// Query the operating system for the number of bytes of address space
+    /// mapped from this plugin's library file.
+    /// # Platform support
+    /// On Linux the mappings of the library's file are read from `/proc/self/maps`.
+    /// On Windows the library's module is found with `GetModuleHandleExW` from the address of its
+    /// [create plugin symbol](CREATE_RDYN_SYM_NAME), and the regions allocated from the module's base address
+    /// are walked with `VirtualQuery`.
+    /// Returns `None` on other platforms or if the mappings could not be read.
+    pub fn mapped_bytes(&self) -> Option<usize> {
+        #[cfg(windows)]
+        return mapped_module_bytes(self.create_symbol_address()?);
+        #[cfg(not(windows))]
+        mapped_file_bytes(&self.path)
+    }
+
+    /// Query the operating system for the name the plugin's library is registered under.
+    /// Unlike [path](Self::path) this identifies the loaded library itself, so the same library
+    /// loaded via different paths reports the same name.
+    /// # Platform support
+    /// On Unix this is the file name reported by `dladdr` for the library,
+    /// on Windows the module file name reported by `GetModuleFileNameW`.
+    /// Returns `None` on other platforms or if the name could not be queried.
+    pub fn library_name(&self) -> Option<String> {
+        loaded_module_name(self.create_symbol_address()?)
+    }
+
+    /// Check that the plugin's library is still loaded, by resolving its [create plugin symbol](CREATE_RDYN_SYM_NAME) again
+    /// and, on Unix and Windows, querying the platform for the loaded library containing the symbol's address.
+    /// A plugin that fails the check is referenced after its library was unloaded, which indicates a lifetime bug.
+    /// # Limitations
+    /// This is a cheap diagnostic and cannot catch every use of unloaded code. It only checks that the library
+    /// is loaded when called, so a library that was unloaded and loaded again, possibly at another address,
+    /// passes the check even though pointers into the old mapping are stale. Code from the library kept
+    /// elsewhere, such as systems and component drop functions, is not checked.
+    ///
+    /// Plugins created with [RustDynPlugin::from_static] fail the check unless the host itself exports the symbol.
+    pub fn verify(&self) -> bool {
+        match self.create_symbol_address() {
+            Some(address) => cfg!(not(any(unix, windows))) || loaded_module_name(address).is_some(),
+            None => false,
+        }
+    }
+
+    /// The address the [create plugin symbol](CREATE_RDYN_SYM_NAME) resolves to in the plugin's library.
+    /// Plugins loaded from distinct libraries should never share this address,
+    /// if they do the symbol of one library is shadowing the other's.
+    pub fn create_symbol_address(&self) -> Option<*const c_void> {
+        let create_plugin_sym: Symbol<CreateRDynPlugin> =
+            unsafe { self.library.get(CREATE_RDYN_SYM_NAME) }.ok()?;
+        Some(*create_plugin_sym as *const c_void)
+    }
+
+    /// The build profile the plugin was compiled with, `"debug"` or `"release"`,
+    /// returns `None` if the plugin does not export the [build profile symbol](BUILD_PROFILE_RDYN_SYM_NAME).
+    #[inline]
+    pub fn build_profile(&self) -> Option<String> {
+        read_rdyn_build_profile(&self.library)
+    }
+
+    /// The version of the host's API the plugin was compiled against,
+    /// returns `None` if the plugin does not export the [host API version symbol](HOST_API_VERSION_RDYN_SYM_NAME).
+    #[inline]
+    pub fn host_api_version(&self) -> Option<u32> {
+        read_rdyn_host_api_version(&self.library)
+    }
+
+    /// Save the plugin's state via its [snapshot symbol](SNAPSHOT_RDYN_SYM_NAME),
+    /// returns `None` if the plugin does not export the symbol.
+    pub fn snapshot(&self, world: &mut World) -> Option<Vec<u8>> {
+        let snapshot_sym: Symbol<RDynSnapshot> =
+            unsafe { self.library.get(SNAPSHOT_RDYN_SYM_NAME) }.ok()?;
+        Some(snapshot_sym(world))
+    }
+
+    /// Restore state saved by a previous version of the plugin via its [restore symbol](RESTORE_RDYN_SYM_NAME),
+    /// returns false if the plugin does not export the symbol.
+    pub fn restore(&self, world: &mut World, state: &[u8]) -> bool {
+        let restore_sym: Symbol<RDynRestore> =
+            match unsafe { self.library.get(RESTORE_RDYN_SYM_NAME) } {
+                Ok(restore_sym) => restore_sym,
+                Err(_) => return false,
+            };
+        restore_sym(world, state);
+        true
+    }
+
+    /// Tear the plugin down via its [shutdown symbol](SHUTDOWN_RDYN_SYM_NAME),
+    /// returns false if the plugin does not export the symbol.
+    pub fn shutdown(&self, world: &mut World) -> bool {
+        let shutdown_sym: Symbol<RDynShutdown> =
+            match unsafe { self.library.get(SHUTDOWN_RDYN_SYM_NAME) } {
+                Ok(shutdown_sym) => shutdown_sym,
+                Err(_) => return false,
+            };
+        shutdown_sym(world);
+        true
+    }
+
+    /// The version the plugin reported via the [version symbol](VERSION_RDYN_SYM_NAME) when it was loaded,
+    /// `None` if it does not export the symbol, such as plugins created with [RustDynPlugin::from_static].
+    #[inline]
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Set the version reported by [RustDynPlugin::version], such as for a plugin created in process
+    /// that cannot export a [version symbol](VERSION_RDYN_SYM_NAME).
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// The capabilities the plugin declares via the [capabilities symbol](CAPABILITIES_RDYN_SYM_NAME),
+    /// none if it does not export the symbol.
+    #[inline]
+    pub fn capabilities(&self) -> Vec<String> {
+        read_rdyn_capabilities(&self.library)
+    }
+
+    /// The host flags the plugin requires via the [required flags symbol](REQUIRED_FLAGS_RDYN_SYM_NAME),
+    /// none if it does not export the symbol.
+    #[inline]
+    pub fn required_flags(&self) -> Vec<String> {
+        read_rdyn_required_flags(&self.library)
+    }
+}
+
+/// Get the name of the loaded module containing the specified address.
+#[cfg(unix)]
+fn loaded_module_name(address: *const c_void) -> Option<String> {
+    let mut info = std::mem::MaybeUninit::<libc::Dl_info>::uninit();
+    if unsafe { libc::dladdr(address, info.as_mut_ptr()) } == 0 {
+        return None;
+    }
+
+    let file_name = unsafe { info.assume_init() }.dli_fname;
+    if file_name.is_null() {
+        return None;
+    }
+    let file_name = unsafe { std::ffi::CStr::from_ptr(file_name) };
+    Some(file_name.to_string_lossy().into_owned())
+}
+
+/// Get the name of the loaded module containing the specified address.
+#[cfg(windows)]
+fn loaded_module_name(address: *const c_void) -> Option<String> {
+    use std::os::windows::ffi::OsStringExt;
+
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+    const MAX_MODULE_NAME_LEN: usize = 32768;
+
+    extern "system" {
+        fn GetModuleHandleExW(
+            flags: u32,
+            module_name: *const c_void,
+            module: *mut *mut c_void,
+        ) -> i32;
+        fn GetModuleFileNameW(module: *mut c_void, file_name: *mut u16, size: u32) -> u32;
+    }
+
+    let mut module = std::ptr::null_mut();
+    let flags =
+        GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+    if unsafe { GetModuleHandleExW(flags, address, &mut module) } == 0 {
+        return None;
+    }
+
+    let mut file_name = vec![0u16; MAX_MODULE_NAME_LEN];
+    let len = unsafe { GetModuleFileNameW(module, file_name.as_mut_ptr(), file_name.len() as u32) };
+    if len == 0 {
+        return None;
+    }
+    file_name.truncate(len as usize);
+    Some(
+        std::ffi::OsString::from_wide(&file_name)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+#[cfg(not(any(unix, windows)))]
+fn loaded_module_name(_address: *const c_void) -> Option<String> {
+    None
+}
+
+/// Sum the sizes of all memory mappings of the file at the specified path in the current process.
+#[cfg(target_os = "linux")]
+fn mapped_file_bytes(path: &std::path::Path) -> Option<usize> {
+    let canonical_path = std::fs::canonicalize(path).ok()?;
+    let maps = std::fs::read_to_string("/proc/self/maps").ok()?;
+
+    let mapped_bytes = maps
+        .lines()
+        .filter_map(|line| {
+            // Format: "start-end perms offset dev inode pathname"
+            let mut fields = line.splitn(6, ' ');
+            let range = fields.next()?;
+            let pathname = fields.nth(4)?.trim_start();
+            if std::path::Path::new(pathname) != canonical_path {
+                return None;
+            }
+
+            let (start, end) = range.split_once('-')?;
+            let start = usize::from_str_radix(start, 16).ok()?;
+            let end = usize::from_str_radix(end, 16).ok()?;
+            Some(end - start)
+        })
+        .sum();
+    Some(mapped_bytes)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn mapped_file_bytes(_path: &std::path::Path) -> Option<usize> {
+    None
+}
+
+/// Sum the sizes of all memory regions of the loaded module containing the specified address.
+#[cfg(windows)]
+fn mapped_module_bytes(address: *const c_void) -> Option<usize> {
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+
+    /// `MEMORY_BASIC_INFORMATION`, the `PartitionId` of 64-bit targets fits in the padding before `RegionSize`.
+    #[repr(C)]
+    struct MemoryBasicInformation {
+        base_address: *mut c_void,
+        allocation_base: *mut c_void,
+        allocation_protect: u32,
+        region_size: usize,
+        state: u32,
+        protect: u32,
+        kind: u32,
+    }
+
+    extern "system" {
+        fn GetModuleHandleExW(
+            flags: u32,
+            module_name: *const c_void,
+            module: *mut *mut c_void,
+        ) -> i32;
+        fn VirtualQuery(
+            address: *const c_void,
+            buffer: *mut MemoryBasicInformation,
+            length: usize,
+        ) -> usize;
+    }
+
+    // A module's handle is its base address, every region of its image is allocated from it.
+    let mut module = std::ptr::null_mut();
+    let flags =
+        GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+    if unsafe { GetModuleHandleExW(flags, address, &mut module) } == 0 {
+        return None;
+    }
+
+    let mut mapped_bytes = 0;
+    let mut region_address = module as usize;
+    loop {
+        let mut info = std::mem::MaybeUninit::<MemoryBasicInformation>::uninit();
+        let written = unsafe {
+            VirtualQuery(
+                region_address as *const c_void,
+                info.as_mut_ptr(),
+                std::mem::size_of::<MemoryBasicInformation>(),
+            )
+        };
+        if written == 0 {
+            break;
+        }
+        let info = unsafe { info.assume_init() };
+        if info.allocation_base != module {
+            break;
+        }
+        mapped_bytes += info.region_size;
+        region_address = info.base_address as usize + info.region_size;
+    }
+
+    match mapped_bytes {
+        0 => None,
+        mapped_bytes => Some(mapped_bytes),
     }
 }
 
 /// Load a rust dynamic plugin from the specified path.
 /// # Unsafety
-/// Undefined behaviour expected if the symbol loaded from the symbol named 
+/// Undefined behaviour expected if the symbol loaded from the symbol named
 /// [Create RDyn Plugin Symbol Name](CREATE_RDYN_SYM_NAME) within the loaded library
 /// does not match the function signature [CreateRDynPlugin]
+/// # Symbol scoping
+/// Every plugin exports the same unmangled symbol names. The symbols are always resolved through
+/// the handle of the library being loaded, so each plugin is created by its own library.
+/// However if a library is made globally visible, such as by opening it with `RTLD_GLOBAL` on Unix
+/// (the default for `dlopen` on macOS) or by linking against it, its symbols can take precedence over
+/// those of libraries loaded after it wherever symbols are resolved through the global scope.
+/// Libraries should be loaded with local symbol scoping (`RTLD_LOCAL`), which is the default on Linux.
+/// # Errors
+/// Returns [ModLoadError::WrongPlatform] without opening the library if the file's header shows it
+/// was not built for the platform of the host, see [check_platform].
+/// Returns [ModLoadError::LibraryOpenFailed] if the library could not be opened, and
+/// [ModLoadError::MissingCreateSymbol] if it was opened but does not export the create plugin symbol.
 #[inline]
-pub fn load_rdyn_plugin(path: &str) -> Option<RustDynPlugin> {
-    let library = Library::new(path).ok()?;
-    let create_plugin_sym: Symbol<CreateRDynPlugin> =
-        unsafe { library.get(CREATE_RDYN_SYM_NAME) }.ok()?;
-    let plugin = create_plugin_sym();
-    Some(RustDynPlugin { library, plugin })
+pub fn load_rdyn_plugin(path: &str) -> Result<RustDynPlugin, ModLoadError> {
+    load_rdyn_plugin_with_context(path, None)
+}
+
+/// Load a rust dynamic plugin from the specified path,
+/// passing the [HostContext] to the plugin before it is created if one is provided.
+/// # Unsafety
+/// Undefined behaviour expected if the symbols loaded from [CREATE_RDYN_SYM_NAME]
+/// and [INSTALL_HOST_CONTEXT_SYM_NAME] within the loaded library do not match
+/// the function signatures [CreateRDynPlugin] and [InstallRDynHostContext]
+pub fn load_rdyn_plugin_with_context(
+    path: &str,
+    host_context: Option<&HostContext>,
+) -> Result<RustDynPlugin, ModLoadError> {
+    let library = load_rdyn_library(path)?;
+    if let Some(host_context) = host_context {
+        library.install_host_context(host_context);
+    }
+    library.into_plugin()
+}
+
+/// A rust dynamic plugin library that has been opened but from which no plugin has been created yet,
+/// giving control over when and how often the plugin's create function is called.
+/// # Example
+/// ```no_run
+/// # use rdyn_plugins::*;
+/// let library = load_rdyn_library("plugins/plugin.dll").unwrap();
+/// let first = library.create_plugin().unwrap();
+/// let second = library.create_plugin().unwrap();
+/// assert_eq!(first.name(), second.name());
+/// ```
+#[derive(Debug)]
+pub struct LoadedLibrary {
+    /// The opened library.
+    pub library: Library,
+    /// The path the library was opened from.
+    pub path: PathBuf,
+}
+
+impl LoadedLibrary {
+    /// The [create plugin symbol](CREATE_RDYN_SYM_NAME) exported by the library.
+    /// Only valid to call if the library's [ABI](LoadedLibrary::abi) is [RDynAbi::Rust].
+    /// # Unsafety
+    /// Undefined behaviour expected if the symbol does not match the function signature [CreateRDynPlugin]
+    pub fn create_symbol(&self) -> Result<Symbol<'_, CreateRDynPlugin>, ModLoadError> {
+        unsafe { self.library.get(CREATE_RDYN_SYM_NAME) }
+            .map_err(|_| ModLoadError::MissingCreateSymbol)
+    }
+
+    /// The ABI of the library's create plugin symbol, as reported by its [ABI symbol](ABI_RDYN_SYM_NAME),
+    /// or [ModLoadError::UnsupportedAbi] if the host does not support it.
+    /// # Unsafety
+    /// Undefined behaviour expected if the symbol does not match the function signature [RDynAbiName]
+    pub fn abi(&self) -> Result<RDynAbi, ModLoadError> {
+        match read_rdyn_abi_name(&self.library) {
+            Some(name) => {
+                RDynAbi::from_name(&name).ok_or(ModLoadError::UnsupportedAbi { found: name })
+            }
+            None => Ok(RDynAbi::Rust),
+        }
+    }
+
+    /// Check the layout of [RDynReturn] reported by the library's [layout symbol](LAYOUT_RDYN_SYM_NAME)
+    /// matches the host's, libraries that do not export the symbol are not checked.
+    /// This is done before the plugin is created, as creating it with a different layout is near certain to crash.
+    /// # Unsafety
+    /// Undefined behaviour expected if the symbol does not match the function signature [RDynLayout]
+    pub fn check_layout(&self) -> Result<(), ModLoadError> {
+        match read_rdyn_layout(&self.library) {
+            Some(layout) => layout.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Create a new instance of the plugin, borrowing the library so that it stays loaded while the plugin exists.
+    /// Can be called any number of times.
+    ///
+    /// The plugin's name is read under [catch_unwind](std::panic::catch_unwind) before it is returned,
+    /// and a plugin whose `name` panics gives [ModLoadError::InvalidPlugin] instead. Only a panicking `name` is caught,
+    /// a plugin that is grossly invalid, such as one built against a mismatched ABI, still crashes the process.
+    /// A panic can only be caught across the library boundary if the host and plugin are built with `-C prefer-dynamic`.
+    ///
+    /// With the "catch_panics" feature a panic while creating the plugin is caught
+    /// and returned as [ModLoadError::CreatePanicked], with the same requirements.
+    pub fn create_plugin(&self) -> Result<RDynInterface<'_, dyn Plugin>, ModLoadError> {
+        Ok(RDynInterface {
+            interface: self.create_checked()?,
+            _library: PhantomData,
+        })
+    }
+
+    /// Check the library's layout, then create the plugin with [create_guarded], see [LoadedLibrary::create_plugin].
+    fn create_checked(&self) -> Result<RDynReturn, ModLoadError> {
+        self.check_layout()?;
+        let create_plugin = CreatePluginFn::get(&self.library, self.abi()?)?;
+        create_guarded(|| create_plugin.call())
+    }
+
+    /// Pass the [HostContext] to the library if it exports the
+    /// [install host context symbol](INSTALL_HOST_CONTEXT_SYM_NAME), returning whether it does.
+    /// Should be called before any plugin is created.
+    /// # Unsafety
+    /// Undefined behaviour expected if the symbol does not match the function signature [InstallRDynHostContext]
+    pub fn install_host_context(&self, host_context: &HostContext) -> bool {
+        let install_host_context_sym: Option<Symbol<InstallRDynHostContext>> =
+            unsafe { self.library.get(INSTALL_HOST_CONTEXT_SYM_NAME) }.ok();
+        match install_host_context_sym {
+            Some(install_host_context_sym) => {
+                install_host_context_sym(host_context);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Create the plugin once, storing it along with the library it was created from.
+    pub fn into_plugin(self) -> Result<RustDynPlugin, ModLoadError> {
+        let plugin = {
+            #[cfg(feature = "trace")]
+            let _create_span = info_span!(target: crate::LOG_TARGET, "create").entered();
+            self.create_checked()?
+        };
+        Ok(RustDynPlugin {
+            plugin,
+            version: read_rdyn_version(&self.library),
+            library: self.library,
+            modified: file_modified(&self.path),
+            path: self.path,
+            build_succeeded: false,
+        })
+    }
+}
+
+/// The modified time of a file, `None` if it cannot be read.
+pub(crate) fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The message of a caught panic, if it is a string.
+#[cfg(feature = "catch_panics")]
+pub(crate) fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+        .to_string()
+}
+
+/// Create a plugin with the specified create plugin function and make a guarded first access to it,
+/// see [LoadedLibrary::create_plugin]. An invalid plugin is leaked, as it cannot be safely dropped.
+fn create_guarded(create_plugin: impl FnOnce() -> RDynReturn) -> Result<RDynReturn, ModLoadError> {
+    #[cfg(feature = "catch_panics")]
+    let plugin = std::panic::catch_unwind(AssertUnwindSafe(create_plugin)).map_err(|panic| {
+        ModLoadError::CreatePanicked {
+            message: panic_message(&*panic),
+        }
+    })?;
+    #[cfg(not(feature = "catch_panics"))]
+    let plugin = create_plugin();
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| plugin.name().len())) {
+        Ok(_) => Ok(plugin),
+        Err(_) => {
+            std::mem::forget(plugin);
+            Err(ModLoadError::InvalidPlugin)
+        }
+    }
+}
+
+/// Open a rust dynamic plugin library from the specified path without creating the plugin.
+/// # Errors
+/// Returns [ModLoadError::WrongPlatform] without opening the library if the file's header shows it
+/// was not built for the platform of the host, and [ModLoadError::LibraryOpenFailed] if it could not be opened.
+pub fn load_rdyn_library(path: &str) -> Result<LoadedLibrary, ModLoadError> {
+    check_platform(path)?;
+    let library = {
+        #[cfg(feature = "trace")]
+        let _open_span = info_span!(target: crate::LOG_TARGET, "open").entered();
+        Library::new(path).map_err(ModLoadError::LibraryOpenFailed)?
+    };
+    Ok(LoadedLibrary {
+        library,
+        path: PathBuf::from(path),
+    })
+}
+
+/// Read the load priority of a rust dynamic plugin from the specified path without creating the plugin.
+/// Returns [DEFAULT_RDYN_PRIORITY] if the library could not be opened or does not export a priority.
+/// # Unsafety
+/// Undefined behaviour expected if the symbol loaded from [PRIORITY_RDYN_SYM_NAME]
+/// does not match the function signature [RDynPriority]
+pub fn peek_rdyn_priority(path: &str) -> i32 {
+    Library::new(path)
+        .ok()
+        .and_then(|library| read_rdyn_priority(&library))
+        .unwrap_or(DEFAULT_RDYN_PRIORITY)
+}
+
+/// Read the load priority exported by an already loaded library, if any.
+pub(crate) fn read_rdyn_priority(library: &Library) -> Option<i32> {
+    let priority_sym: Symbol<RDynPriority> = unsafe { library.get(PRIORITY_RDYN_SYM_NAME) }.ok()?;
+    Some(priority_sym())
+}
+
+/// Read the build profile exported by an already loaded library, if any.
+pub(crate) fn read_rdyn_build_profile(library: &Library) -> Option<String> {
+    let build_profile_sym: Symbol<RDynBuildProfile> =
+        unsafe { library.get(BUILD_PROFILE_RDYN_SYM_NAME) }.ok()?;
+    // Copied out as the string lives in the library.
+    Some(build_profile_sym().to_string())
+}
+
+/// Read the name of the ABI exported by an already loaded library, if any.
+pub(crate) fn read_rdyn_abi_name(library: &Library) -> Option<String> {
+    let abi_sym: Symbol<RDynAbiName> = unsafe { library.get(ABI_RDYN_SYM_NAME) }.ok()?;
+    // Copied out as the string lives in the library.
+    Some(abi_sym().to_string())
+}
+
+/// Read the host API version exported by an already loaded library, if any.
+pub(crate) fn read_rdyn_host_api_version(library: &Library) -> Option<u32> {
+    let host_api_version_sym: Symbol<RDynHostApiVersion> =
+        unsafe { library.get(HOST_API_VERSION_RDYN_SYM_NAME) }.ok()?;
+    Some(host_api_version_sym())
+}
+
+/// Read the layout of [RDynReturn] exported by an already loaded library, if any.
+pub(crate) fn read_rdyn_layout(library: &Library) -> Option<RDynReturnLayout> {
+    let layout_sym: Symbol<RDynLayout> = unsafe { library.get(LAYOUT_RDYN_SYM_NAME) }.ok()?;
+    Some(layout_sym())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::App;
+
+    struct ValidPlugin;
+
+    impl Plugin for ValidPlugin {
+        fn build(&self, _app: &mut App) {}
+    }
+
+    /// A plugin whose name cannot be read, standing in for a plugin built against a mismatched ABI.
+    struct PanickingNamePlugin;
+
+    impl Plugin for PanickingNamePlugin {
+        fn build(&self, _app: &mut App) {}
+
+        fn name(&self) -> &str {
+            panic!("name of an invalid plugin")
+        }
+    }
+
+    fn create_valid_plugin() -> RDynReturn {
+        Box::new(ValidPlugin)
+    }
+
+    fn create_panicking_name_plugin() -> RDynReturn {
+        Box::new(PanickingNamePlugin)
+    }
+
+    #[test]
+    fn create_guarded_returns_valid_plugin() {
+        let plugin = create_guarded(create_valid_plugin).unwrap();
+        assert_eq!(plugin.name(), std::any::type_name::<ValidPlugin>());
+    }
+
+    #[test]
+    fn create_guarded_rejects_panicking_name() {
+        assert!(matches!(
+            create_guarded(create_panicking_name_plugin),
+            Err(ModLoadError::InvalidPlugin)
+        ));
+    }
+
+    #[cfg(feature = "catch_panics")]
+    #[test]
+    fn create_guarded_catches_panicking_constructor() {
+        fn create_panicking_plugin() -> RDynReturn {
+            panic!("constructor failed")
+        }
+
+        assert!(matches!(
+            create_guarded(create_panicking_plugin),
+            Err(ModLoadError::CreatePanicked { message }) if message == "constructor failed"
+        ));
+    }
 }
@@ -0,0 +1,73 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The library of a mod loaded from bytes, written where the platform's dynamic loader can open it.
+/// The file is removed when this is dropped, which is safe once the library has been opened.
+pub(crate) struct EmbeddedModFile {
+    path: PathBuf,
+}
+
+impl EmbeddedModFile {
+    /// Write the bytes of a mod's library to an anonymous in-memory file, which is not visible on disk.
+    ///
+    /// The file is never closed. The dynamic loader identifies libraries by the path they were opened from,
+    /// so if the file's descriptor were reused its path would refer to this library for any later mod.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn write(name: &str, bytes: &[u8]) -> io::Result<Self> {
+        use std::{
+            ffi::CString,
+            os::unix::io::{FromRawFd, IntoRawFd},
+        };
+
+        let memfd_name = CString::new(name.replace('\0', "")).unwrap_or_default();
+        let fd = unsafe { libc::memfd_create(memfd_name.as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut memfd = unsafe { File::from_raw_fd(fd) };
+        memfd.write_all(bytes)?;
+
+        Ok(Self {
+            path: PathBuf::from(format!("/proc/self/fd/{}", memfd.into_raw_fd())),
+        })
+    }
+
+    /// Write the bytes of a mod's library to a uniquely named file in the temporary directory,
+    /// as the platform's dynamic loader can only open libraries from files.
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn write(name: &str, bytes: &[u8]) -> io::Result<Self> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static EMBEDDED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let file_name = Path::new(name)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!(
+            "rdyn-embedded-{}-{}-{}",
+            std::process::id(),
+            EMBEDDED_COUNT.fetch_add(1, Ordering::Relaxed),
+            file_name
+        ));
+        File::create(&path)?.write_all(bytes)?;
+        Ok(Self { path })
+    }
+
+    /// The path the library can be opened from.
+    #[inline]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Drop for EmbeddedModFile {
+    fn drop(&mut self) {
+        // Windows does not allow removing a loaded library, in which case the file is left behind.
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,177 @@
+use std::{error::Error, fmt::Display, io, path::PathBuf};
+
+use crate::{
+    dyn_api::{RDynReturnLayout, CREATE_RDYN_SYM_NAME},
+    platform::{BinaryFormat, BinaryPlatform},
+};
+
+/// Errors that can occur while loading a rust dynamic plugin.
+#[derive(Debug)]
+pub enum ModLoadError {
+    /// The plugin file could not be read.
+    Io(io::Error),
+    /// The plugin file is not a dynamic library built for the platform of the host.
+    WrongPlatform {
+        expected: BinaryPlatform,
+        found: BinaryPlatform,
+    },
+    /// The plugin library could not be opened by the platform's dynamic loader.
+    LibraryOpenFailed(io::Error),
+    /// The plugin library was opened but does not export the [create plugin symbol](crate::CREATE_RDYN_SYM_NAME).
+    MissingCreateSymbol,
+    /// There are no directories or patterns to reload mods from, as mods were not loaded from any.
+    NoModSources,
+    /// The path mods were to be loaded from is a file, not a directory.
+    NotADirectory(PathBuf),
+    /// The plugin declares capabilities that are not granted by the host's [CapabilityPolicy](crate::CapabilityPolicy).
+    CapabilityDenied { denied: Vec<String> },
+    /// The plugin requires flags that are not provided by the host's
+    /// [ModLoaderSettings::host_flags](crate::ModLoaderSettings::host_flags).
+    MissingFlags { missing: Vec<String> },
+    /// The plugin was compiled against a different version of the host's API than
+    /// [ModLoaderSettings::expected_host_api](crate::ModLoaderSettings::expected_host_api),
+    /// `found` is `None` if the plugin does not export the [host API version symbol](crate::HOST_API_VERSION_RDYN_SYM_NAME).
+    HostApiMismatch { expected: u32, found: Option<u32> },
+    /// The plugin was compiled with a different layout of [RDynReturn](crate::RDynReturn) than the host,
+    /// so creating it would likely crash.
+    LayoutMismatch {
+        expected: RDynReturnLayout,
+        found: RDynReturnLayout,
+    },
+    /// The plugin created by the library panicked when its name was first read, likely as it was built against
+    /// a different ABI. See [LoadedLibrary::create_plugin](crate::LoadedLibrary::create_plugin).
+    InvalidPlugin,
+    /// A plugin with the same name is already loaded and the
+    /// [DuplicateModPolicy](crate::DuplicateModPolicy) skips duplicates.
+    DuplicateName { name: String, loaded_from: PathBuf },
+    /// The plugin's create function uses an ABI the host does not support, see [RDynAbi](crate::RDynAbi).
+    UnsupportedAbi { found: String },
+    /// The plugin's create function panicked, caught with the "catch_panics" feature.
+    CreatePanicked { message: String },
+    /// The task opening the plugin for "load_mods_async" panicked,
+    /// such as when its create function panics without the "catch_panics" feature.
+    OpenTaskPanicked,
+    /// The [DeferredPlugin](crate::DeferredPlugin) has already been built.
+    AlreadyBuilt,
+    /// The [DeferredPlugin](crate::DeferredPlugin) was built into a running app and added systems to the schedule,
+    /// which cannot be added once the app is running and have been discarded.
+    ScheduleModifiedWhileRunning { discarded_systems: usize },
+    /// No mod with the specified name is stored in the [ModLoaderData](crate::ModLoaderData) resource.
+    ModNotLoaded(String),
+}
+
+impl Display for ModLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModLoadError::Io(err) => write!(f, "could not read plugin file: {}", err),
+            ModLoadError::NotADirectory(path) => write!(
+                f,
+                "mods folder '{}' is a file, expected a directory",
+                path.display()
+            ),
+            ModLoadError::WrongPlatform { expected, found }
+                if found.format == BinaryFormat::Unknown =>
+            {
+                write!(f, "file is not a dynamic library, expected {}", expected)
+            }
+            ModLoadError::WrongPlatform { expected, found } => write!(
+                f,
+                "plugin was built for a different platform, expected {} but found {}",
+                expected, found
+            ),
+            ModLoadError::LibraryOpenFailed(err) => write!(
+                f,
+                "could not open plugin library: {}. Check that the file is a dynamic library \
+                 and that any libraries it depends on can be found",
+                err
+            ),
+            ModLoadError::MissingCreateSymbol => write!(
+                f,
+                "plugin library was opened but does not export the '{}' symbol. Check that the plugin crate \
+                 is built with `crate-type = [\"dylib\"]` and that its entry plugin derives `RDynPlugin`",
+                String::from_utf8_lossy(CREATE_RDYN_SYM_NAME)
+            ),
+            ModLoadError::NoModSources => write!(
+                f,
+                "no mods were loaded from a directory or pattern, so there are none to reload"
+            ),
+            ModLoadError::CapabilityDenied { denied } => write!(
+                f,
+                "plugin declares capabilities that are not granted: {}",
+                denied.join(", ")
+            ),
+            ModLoadError::MissingFlags { missing } => write!(
+                f,
+                "plugin requires flags that are not provided by the host: {}",
+                missing.join(", ")
+            ),
+            ModLoadError::HostApiMismatch {
+                expected,
+                found: Some(found),
+            } => write!(
+                f,
+                "plugin was compiled against host API version {} but the host expects version {}",
+                found, expected
+            ),
+            ModLoadError::HostApiMismatch {
+                expected,
+                found: None,
+            } => write!(
+                f,
+                "plugin does not report the host API version it was compiled against, the host expects version {}",
+                expected
+            ),
+            ModLoadError::LayoutMismatch { expected, found } => write!(
+                f,
+                "plugin was compiled with a different plugin layout, expected size {} and alignment {} \
+                 but found size {} and alignment {}. Rebuild the plugin with the host's versions of rustc and bevy",
+                expected.size, expected.align, found.size, found.align
+            ),
+            ModLoadError::InvalidPlugin => write!(
+                f,
+                "plugin created by the library is invalid, it was likely built with a different version \
+                 of rustc or bevy than the host"
+            ),
+            ModLoadError::DuplicateName { name, loaded_from } => write!(
+                f,
+                "a plugin named '{}' is already loaded from '{}'",
+                name,
+                loaded_from.display()
+            ),
+            ModLoadError::UnsupportedAbi { found } => write!(
+                f,
+                "plugin's create function uses the '{}' ABI, which the host does not support",
+                found
+            ),
+            ModLoadError::CreatePanicked { message } => {
+                write!(f, "plugin panicked while being created: {}", message)
+            }
+            ModLoadError::OpenTaskPanicked => {
+                write!(f, "plugin panicked while being opened on a background task")
+            }
+            ModLoadError::AlreadyBuilt => write!(f, "plugin has already been built"),
+            ModLoadError::ScheduleModifiedWhileRunning { discarded_systems } => write!(
+                f,
+                "plugin added {} systems to the schedule while being built into a running app, \
+                 which have been discarded. Add them with \"add_plugin_system\" instead",
+                discarded_systems
+            ),
+            ModLoadError::ModNotLoaded(name) => write!(f, "no mod named '{}' is loaded", name),
+        }
+    }
+}
+
+impl Error for ModLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ModLoadError::Io(err) | ModLoadError::LibraryOpenFailed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ModLoadError {
+    fn from(err: io::Error) -> Self {
+        ModLoadError::Io(err)
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use libloading::{Library, Symbol};
+
+use crate::{dyn_api::*, error::*};
+
+/// Name of the optional symbol exported by a plugin to declare the host flags it requires,
+/// such as `"full_version"`. Plugins without the symbol require none.
+/// The plugin is skipped unless every flag is in [ModLoaderSettings::host_flags](crate::ModLoaderSettings::host_flags),
+/// allowing a host to gate mods on what is available, such as a demo and a full version of a game.
+/// # Example
+/// ```
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_required_flags() -> Vec<String> {
+///     vec!["full_version".to_string()]
+/// }
+/// ```
+pub const REQUIRED_FLAGS_RDYN_SYM_NAME: &[u8] = b"_rdyn_required_flags";
+/// Type that represents the function signature of the required flags symbol.
+pub type RDynRequiredFlags = fn() -> Vec<String>;
+
+/// Check the flags required by a mod against the flags provided by the host,
+/// returning [ModLoadError::MissingFlags] listing every required flag that is not provided.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// # use std::collections::HashSet;
+/// let host_flags = HashSet::from(["full_version".to_string()]);
+/// assert!(check_required_flags(&["full_version".to_string()], &host_flags).is_ok());
+/// assert!(check_required_flags(&["beta".to_string()], &host_flags).is_err());
+/// ```
+pub fn check_required_flags(
+    required_flags: &[String],
+    host_flags: &HashSet<String>,
+) -> Result<(), ModLoadError> {
+    let missing: Vec<String> = required_flags
+        .iter()
+        .filter(|flag| !host_flags.contains(*flag))
+        .cloned()
+        .collect();
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(ModLoadError::MissingFlags { missing }),
+    }
+}
+
+/// Read the flags required by a rust dynamic plugin from the specified path without creating the plugin,
+/// such as to list the mods that are unavailable in the current version of the host.
+/// # Unsafety
+/// Undefined behaviour expected if the symbol loaded from [REQUIRED_FLAGS_RDYN_SYM_NAME]
+/// does not match the function signature [RDynRequiredFlags]
+pub fn peek_rdyn_required_flags(path: &str) -> Result<Vec<String>, ModLoadError> {
+    let loaded_library = load_rdyn_library(path)?;
+    Ok(read_rdyn_required_flags(&loaded_library.library))
+}
+
+/// Read the flags required by an already loaded library, none if it does not export the symbol.
+pub(crate) fn read_rdyn_required_flags(library: &Library) -> Vec<String> {
+    let required_flags_sym: Option<Symbol<RDynRequiredFlags>> =
+        unsafe { library.get(REQUIRED_FLAGS_RDYN_SYM_NAME) }.ok();
+    required_flags_sym
+        .map(|required_flags_sym| required_flags_sym())
+        .unwrap_or_default()
+}
@@ -0,0 +1,121 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::OnceLock,
+};
+
+/// Name of symbol exported by plugins to receive the [HostContext] before the plugin is created.
+/// This symbol is generated by the RDynPlugin derive with the `#[rdyn_plugin(host_context)]` option.
+pub const INSTALL_HOST_CONTEXT_SYM_NAME: &[u8] = b"_rdyn_install_host_context";
+/// Type that represents the function signature of the install host context symbol.
+pub type InstallRDynHostContext = fn(&HostContext);
+
+/// Signature of a function allocating memory with the host's allocator.
+pub type HostAllocFn = unsafe fn(Layout) -> *mut u8;
+/// Signature of a function freeing memory with the host's allocator.
+pub type HostDeallocFn = unsafe fn(*mut u8, Layout);
+
+/// The entry points of the host's global allocator, shared with plugins
+/// so that memory can be freely passed between the host and plugins.
+#[derive(Debug, Clone, Copy)]
+pub struct HostAllocatorFns {
+    pub alloc: HostAllocFn,
+    pub dealloc: HostDeallocFn,
+}
+
+impl HostAllocatorFns {
+    /// The entry points of the global allocator of the calling binary,
+    /// call this from the host to share its allocator.
+    pub fn current() -> Self {
+        unsafe fn alloc(layout: Layout) -> *mut u8 {
+            std::alloc::alloc(layout)
+        }
+        unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+            std::alloc::dealloc(ptr, layout)
+        }
+
+        Self { alloc, dealloc }
+    }
+}
+
+/// State shared by the host with each plugin before the plugin is created.
+///
+/// Each dynamic library has its own copy of any global state, which can include its own allocator.
+/// Insert this as a resource on the host to have it passed to every plugin loaded via [ModLoaderExt](crate::ModLoaderExt)
+/// that exports the [install host context symbol](INSTALL_HOST_CONTEXT_SYM_NAME), plugins can then read it with [HostContext::current].
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// let mut app = App::new();
+/// app.insert_resource(HostContext::with_host_allocator())
+///     .load_mods("plugins");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct HostContext {
+    /// The host's allocator, used by plugins that install [HostAllocator] as their global allocator.
+    pub allocator: Option<HostAllocatorFns>,
+    /// The host's tokio runtime, used by plugins to spawn tasks with [spawn_on_host_runtime](crate::spawn_on_host_runtime).
+    #[cfg(feature = "tokio")]
+    pub tokio_runtime: Option<tokio::runtime::Handle>,
+}
+
+static INSTALLED_HOST_CONTEXT: OnceLock<HostContext> = OnceLock::new();
+
+impl HostContext {
+    /// Create a host context sharing the host's global allocator.
+    pub fn with_host_allocator() -> Self {
+        Self {
+            allocator: Some(HostAllocatorFns::current()),
+            #[cfg(feature = "tokio")]
+            tokio_runtime: None,
+        }
+    }
+
+    /// Install the context received from the host into this binary.
+    /// Called by the symbol generated by the RDynPlugin derive, only the first installed context is kept.
+    pub fn install(context: &HostContext) {
+        let _ = INSTALLED_HOST_CONTEXT.set(context.clone());
+    }
+
+    /// The context installed from the host, if any.
+    /// Only available in plugins, and only if the host provided a context.
+    pub fn current() -> Option<&'static HostContext> {
+        INSTALLED_HOST_CONTEXT.get()
+    }
+}
+
+/// A global allocator for plugins that routes all allocations through the host's allocator,
+/// falling back to the system allocator until the host's allocator is installed.
+///
+/// This makes it sound to allocate memory in a plugin and free it in the host, or the reverse,
+/// which is otherwise undefined behaviour whenever the two use different allocators.
+/// # Usage
+/// In the plugin, alongside the RDynPlugin derive with the `#[rdyn_plugin(host_context)]` option:
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: HostAllocator = HostAllocator;
+/// ```
+/// And in the host, insert a [HostContext] created with [HostContext::with_host_allocator].
+/// # Requirements
+/// - The host context is installed before the plugin is created, which is the first plugin code run
+///   by the loader. Any memory the plugin allocates before then, for example from a library
+///   constructor, comes from the system allocator and must not be freed after the host's
+///   allocator is installed.
+/// - The host must outlive every allocation made by the plugin, since its allocator is used to free them.
+pub struct HostAllocator;
+
+unsafe impl GlobalAlloc for HostAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match HostContext::current().and_then(|context| context.allocator) {
+            Some(allocator) => (allocator.alloc)(layout),
+            None => System.alloc(layout),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match HostContext::current().and_then(|context| context.allocator) {
+            Some(allocator) => (allocator.dealloc)(ptr, layout),
+            None => System.dealloc(ptr, layout),
+        }
+    }
+}
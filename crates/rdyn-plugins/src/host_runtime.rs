@@ -0,0 +1,80 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::{runtime::Handle, task::JoinHandle};
+
+use crate::host_context::*;
+
+impl HostContext {
+    /// Share the host's tokio runtime with plugins, see [spawn_on_host_runtime].
+    pub fn with_tokio_runtime(mut self, runtime: Handle) -> Self {
+        self.tokio_runtime = Some(runtime);
+        self
+    }
+
+    /// The tokio runtime shared by the host, if any.
+    /// Only available in plugins, and only if the host provided a runtime.
+    pub fn host_tokio_runtime() -> Option<&'static Handle> {
+        Self::current().and_then(|context| context.tokio_runtime.as_ref())
+    }
+}
+
+/// Spawn a task from a plugin on the tokio runtime shared by the host through its [HostContext],
+/// `None` if the host did not share a runtime.
+///
+/// Each dynamic library has its own copy of tokio, including the thread local state tokio uses to find
+/// the current runtime, which is never set in a plugin's copy by the host's runtime. Functions such as
+/// `tokio::spawn`, `tokio::time::sleep` or `tokio::net::TcpStream::connect` therefore panic when called by a plugin,
+/// even from a task running on the host's runtime. Tasks spawned with this function enter the runtime in the
+/// plugin's copy of tokio whenever they are polled, so they can use tokio as normal.
+/// # Requirements
+/// - The host and every plugin must be compiled with the same version of tokio, with the same tokio features enabled,
+///   as the plugin's copy of tokio operates on the host's runtime. Plugins must also enable the "tokio" feature,
+///   which changes the layout of the [HostContext].
+/// - Plugins must be derived with the `#[rdyn_plugin(host_context)]` option to receive the [HostContext].
+/// - The host must not drop or shut down its runtime until every plugin using it has been unloaded,
+///   and plugins must not block on or shut down the host's runtime.
+/// - Tasks spawned by a plugin run code from its library, so they must have finished,
+///   or been aborted through their [JoinHandle], before the plugin is unloaded.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// // In the host, insert the context as a resource to share the runtime.
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let host_context = HostContext::default().with_tokio_runtime(runtime.handle().clone());
+/// # HostContext::install(&host_context);
+///
+/// // In the plugin, once the context has been installed.
+/// let task = spawn_on_host_runtime(async { 6 * 7 }).unwrap();
+/// assert_eq!(runtime.block_on(task).unwrap(), 42);
+/// ```
+pub fn spawn_on_host_runtime<F>(future: F) -> Option<JoinHandle<F::Output>>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let runtime = HostContext::host_tokio_runtime()?;
+    Some(runtime.spawn(EnterRuntime {
+        runtime: runtime.clone(),
+        future: Box::pin(future),
+    }))
+}
+
+/// Enters a runtime in this binary's copy of tokio whenever the wrapped future is polled.
+struct EnterRuntime<F> {
+    runtime: Handle,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for EnterRuntime<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let _guard = this.runtime.enter();
+        this.future.as_mut().poll(cx)
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fmt::Display, path::PathBuf};
+
+use crate::{capabilities::*, dyn_api::*, flags::*, host_context::*, platform::*, version::*};
+
+/// A diagnostic report of the rdyn symbols exported by a plugin library and their values,
+/// created by [inspect_plugin]. Displays as a human readable summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInspection {
+    /// The path of the inspected file.
+    pub path: PathBuf,
+    /// The platform the file was built for, `None` if it could not be read.
+    pub platform: Option<BinaryPlatform>,
+    /// Why the library could not be opened, in which case no symbols were read.
+    pub open_error: Option<String>,
+    /// Whether the library exports the [create plugin symbol](CREATE_RDYN_SYM_NAME).
+    pub has_create_symbol: bool,
+    /// Whether the library exports the [install host context symbol](INSTALL_HOST_CONTEXT_SYM_NAME).
+    pub has_host_context_symbol: bool,
+    /// The layout reported by the [layout symbol](LAYOUT_RDYN_SYM_NAME).
+    pub layout: Option<RDynReturnLayout>,
+    /// The build profile reported by the [build profile symbol](BUILD_PROFILE_RDYN_SYM_NAME).
+    pub build_profile: Option<String>,
+    /// The version reported by the [host API version symbol](HOST_API_VERSION_RDYN_SYM_NAME).
+    pub host_api_version: Option<u32>,
+    /// The load priority reported by the [load priority symbol](PRIORITY_RDYN_SYM_NAME).
+    pub priority: Option<i32>,
+    /// The capabilities reported by the [capabilities symbol](CAPABILITIES_RDYN_SYM_NAME).
+    pub capabilities: Vec<String>,
+    /// The host flags reported by the [required flags symbol](REQUIRED_FLAGS_RDYN_SYM_NAME).
+    pub required_flags: Vec<String>,
+    /// The version reported by the [version symbol](VERSION_RDYN_SYM_NAME).
+    pub version: Option<String>,
+    /// The name of the plugin, only read if the library exports the create plugin symbol
+    /// and its layout matches the host's.
+    pub name: Option<String>,
+}
+
+/// Inspect the rdyn symbols exported by the plugin library at the specified path, without building the plugin.
+/// Intended for plugin authors to diagnose why a plugin does not load, see the "inspect" example.
+/// The plugin is only created to read its name if it is safe to do so.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// let inspection = inspect_plugin("plugins/missing.so");
+/// assert!(inspection.open_error.is_some());
+/// println!("{}", inspection);
+/// ```
+pub fn inspect_plugin(path: &str) -> PluginInspection {
+    let mut inspection = PluginInspection {
+        path: PathBuf::from(path),
+        platform: BinaryPlatform::read_from(path).ok(),
+        open_error: None,
+        has_create_symbol: false,
+        has_host_context_symbol: false,
+        layout: None,
+        build_profile: None,
+        host_api_version: None,
+        priority: None,
+        capabilities: Vec::new(),
+        required_flags: Vec::new(),
+        version: None,
+        name: None,
+    };
+
+    let loaded_library = match load_rdyn_library(path) {
+        Ok(loaded_library) => loaded_library,
+        Err(err) => {
+            inspection.open_error = Some(err.to_string());
+            return inspection;
+        }
+    };
+    let library = &loaded_library.library;
+    inspection.has_create_symbol = loaded_library.create_symbol().is_ok();
+    inspection.has_host_context_symbol =
+        unsafe { library.get::<InstallRDynHostContext>(INSTALL_HOST_CONTEXT_SYM_NAME) }.is_ok();
+    inspection.layout = read_rdyn_layout(library);
+    inspection.build_profile = read_rdyn_build_profile(library);
+    inspection.host_api_version = read_rdyn_host_api_version(library);
+    inspection.priority = read_rdyn_priority(library);
+    inspection.capabilities = read_rdyn_capabilities(library);
+    inspection.required_flags = read_rdyn_required_flags(library);
+    inspection.version = read_rdyn_version(library);
+    inspection.name = loaded_library
+        .create_plugin()
+        .ok()
+        .map(|plugin| plugin.name().to_string());
+    inspection
+}
+
+impl Display for PluginInspection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn or_missing<T: Display>(value: &Option<T>) -> String {
+            match value {
+                Some(value) => value.to_string(),
+                None => "not exported".to_string(),
+            }
+        }
+
+        writeln!(f, "Plugin: {}", self.path.display())?;
+        match &self.platform {
+            Some(platform) => writeln!(
+                f,
+                "  platform: {} (host is {})",
+                platform,
+                BinaryPlatform::HOST
+            )?,
+            None => writeln!(f, "  platform: could not be read")?,
+        }
+        if let Some(open_error) = &self.open_error {
+            return writeln!(f, "  could not be opened: {}", open_error);
+        }
+
+        writeln!(f, "  name: {}", or_missing(&self.name))?;
+        writeln!(
+            f,
+            "  create symbol: {}",
+            match self.has_create_symbol {
+                true => "exported",
+                false => "not exported",
+            }
+        )?;
+        writeln!(
+            f,
+            "  host context symbol: {}",
+            match self.has_host_context_symbol {
+                true => "exported",
+                false => "not exported",
+            }
+        )?;
+        match self.layout {
+            Some(layout) => writeln!(
+                f,
+                "  layout: size {}, alignment {} ({})",
+                layout.size,
+                layout.align,
+                match layout == RDynReturnLayout::HOST {
+                    true => "matches the host",
+                    false => "does not match the host",
+                }
+            )?,
+            None => writeln!(f, "  layout: not exported")?,
+        }
+        writeln!(
+            f,
+            "  build profile: {} (host is {})",
+            or_missing(&self.build_profile),
+            HOST_BUILD_PROFILE
+        )?;
+        writeln!(
+            f,
+            "  host API version: {}",
+            or_missing(&self.host_api_version)
+        )?;
+        writeln!(f, "  priority: {}", or_missing(&self.priority))?;
+        writeln!(f, "  version: {}", or_missing(&self.version))?;
+        match self.capabilities.is_empty() {
+            true => writeln!(f, "  capabilities: none")?,
+            false => writeln!(f, "  capabilities: {}", self.capabilities.join(", "))?,
+        }
+        match self.required_flags.is_empty() {
+            true => writeln!(f, "  required flags: none"),
+            false => writeln!(f, "  required flags: {}", self.required_flags.join(", ")),
+        }
+    }
+}
@@ -0,0 +1,484 @@
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, ExitStatus, Stdio},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+
+use crate::{mod_loader::*, plugin_systems::*};
+
+/// Command line argument that makes the host executable run as the child process of an isolated mod,
+/// see [run_isolated_child_if_requested].
+pub const ISOLATED_CHILD_ARG: &str = "--rdyn-isolated-child";
+
+/// How long the host waits for an isolated mod's child process to reply to a frame,
+/// before the mod is considered crashed and its process is killed.
+const CHILD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a stopped mod's child process is given to exit by itself before it is killed.
+const CHILD_EXIT_GRACE: Duration = Duration::from_millis(200);
+
+/// Largest resource or name accepted in a frame, so that a corrupt frame cannot exhaust memory.
+const MAX_FRAME_ENTRY_LEN: usize = 64 * 1024 * 1024;
+
+/// A resource whose state is copied between the host and the child processes of isolated mods,
+/// converted to bytes with a format of its choosing.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// struct Score(u32);
+///
+/// impl MirroredResource for Score {
+///     fn encode(&self) -> Vec<u8> {
+///         self.0.to_le_bytes().to_vec()
+///     }
+///
+///     fn decode(bytes: &[u8]) -> Option<Self> {
+///         Some(Score(u32::from_le_bytes(bytes.try_into().ok()?)))
+///     }
+/// }
+/// ```
+pub trait MirroredResource: Send + Sync + Sized + 'static {
+    /// Convert the resource to bytes to be sent to the other process.
+    fn encode(&self) -> Vec<u8>;
+    /// Convert bytes received from the other process back to the resource,
+    /// returns `None` if they are invalid, in which case the resource is left unchanged.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+/// A frame of mirrored resources, each encoded under its registered name.
+type Frame = Vec<(String, Vec<u8>)>;
+
+/// A mirrored resource registered under a name shared by the host and child processes.
+#[derive(Clone)]
+struct MirrorEntry {
+    name: String,
+    encode: fn(&World) -> Option<Vec<u8>>,
+    apply: fn(&mut World, &[u8]),
+}
+
+/// The set of resources mirrored between the host and the child processes of isolated mods.
+/// This is the only state shared with an isolated mod, so it must be registered identically by the host
+/// and the child, typically by building it in one function used by both, see [run_isolated_child_if_requested].
+#[derive(Default, Clone)]
+pub struct MirroredResources {
+    entries: Vec<MirrorEntry>,
+}
+
+impl MirroredResources {
+    /// An empty set of mirrored resources, register resources with [MirroredResources::with].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mirror the resource `R` under the specified name.
+    pub fn with<R: MirroredResource>(mut self, name: &str) -> Self {
+        self.entries.push(MirrorEntry {
+            name: name.to_string(),
+            encode: |world| world.get_resource::<R>().map(R::encode),
+            apply: |world, bytes| match R::decode(bytes) {
+                Some(resource) => world.insert_resource(resource),
+                None => warn!(
+                    target: LOG_TARGET,
+                    "Could not decode mirrored resource {}",
+                    std::any::type_name::<R>()
+                ),
+            },
+        });
+        self
+    }
+
+    /// Encode every registered resource present in the world.
+    fn encode(&self, world: &World) -> Frame {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((entry.name.clone(), (entry.encode)(world)?)))
+            .collect()
+    }
+
+    /// Insert every received resource that is registered into the world.
+    fn apply(&self, world: &mut World, frame: &[(String, Vec<u8>)]) {
+        for (name, bytes) in frame {
+            match self.entries.iter().find(|entry| &entry.name == name) {
+                Some(entry) => (entry.apply)(world, bytes),
+                None => warn!(
+                    target: LOG_TARGET,
+                    "Received unregistered mirrored resource '{}'",
+                    name
+                ),
+            }
+        }
+    }
+}
+
+/// A mod running in a child process, see the "load_mod_isolated" extension method.
+pub struct IsolatedMod {
+    path: PathBuf,
+    child: Child,
+    pipe: Option<ChildPipe>,
+}
+
+/// The pipes to an isolated mod's child process: frames are written to its stdin,
+/// and the frames it writes to its stdout are read by a thread, so that replies can be waited on with a timeout.
+struct ChildPipe {
+    stdin: ChildStdin,
+    // Only accessed through `&mut`, the mutex makes the pipe `Sync` so it can be stored in a resource.
+    replies: Mutex<mpsc::Receiver<io::Result<Frame>>>,
+}
+
+impl IsolatedMod {
+    /// Start a child process of the host executable that loads the mod at the specified path,
+    /// communicating with it over the child's stdin and stdout.
+    pub fn spawn(mod_path: &str) -> io::Result<Self> {
+        let mut child = Command::new(std::env::current_exe()?)
+            .arg(ISOLATED_CHILD_ARG)
+            .arg(mod_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (stdin, mut stdout) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => (stdin, stdout),
+            _ => {
+                let _ = child.kill();
+                return Err(io::Error::other("isolated mod process has no pipes"));
+            }
+        };
+
+        let (sender, replies) = mpsc::channel();
+        let reader = std::thread::Builder::new()
+            .name(format!("isolated mod '{}'", mod_path))
+            .spawn(move || loop {
+                let reply = read_frame(&mut stdout);
+                let failed = reply.is_err();
+                // The receiver is only gone once the mod has been stopped.
+                if sender.send(reply).is_err() || failed {
+                    break;
+                }
+            });
+        if let Err(err) = reader {
+            let _ = child.kill();
+            return Err(err);
+        }
+
+        Ok(Self {
+            path: PathBuf::from(mod_path),
+            child,
+            pipe: Some(ChildPipe {
+                stdin,
+                replies: Mutex::new(replies),
+            }),
+        })
+    }
+
+    /// The path the mod was loaded from.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the mod's child process is still being updated, false once it has crashed or stopped responding.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.pipe.is_some()
+    }
+
+    /// Send the mirrored resources to the child, let it update once and receive the resources it sends back.
+    fn step(&mut self, frame: &[(String, Vec<u8>)]) -> io::Result<Frame> {
+        let pipe = self
+            .pipe
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, "isolated mod stopped"))?;
+        write_frame(&mut pipe.stdin, frame)?;
+        let replies = pipe
+            .replies
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner());
+        match replies.recv_timeout(CHILD_TIMEOUT) {
+            Ok(reply) => reply,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "isolated mod process did not reply",
+            )),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "isolated mod process closed its stdout",
+            )),
+        }
+    }
+
+    /// Stop updating the mod, killing its process if it is still running, and return how the process exited.
+    fn stop(&mut self) -> Option<ExitStatus> {
+        // Closing the child's stdin makes a healthy child exit, and a crashing one is given a moment to finish.
+        self.pipe = None;
+        let stopping = Instant::now();
+        while stopping.elapsed() < CHILD_EXIT_GRACE {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return Some(status);
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let _ = self.child.kill();
+        self.child.wait().ok()
+    }
+}
+
+impl Drop for IsolatedMod {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Stores the mods loaded via the "load_mod_isolated" extension method.
+#[derive(Default)]
+pub struct IsolatedMods {
+    /// The isolated mods in the order they were loaded, including those that have stopped.
+    pub mods: Vec<IsolatedMod>,
+}
+
+/// Sets up the [MirroredResources] and the [sync_isolated_mods] system,
+/// required for mods loaded via the "load_mod_isolated" extension method to be updated.
+pub struct IsolationPlugin {
+    /// The resources mirrored with every isolated mod, registered identically by the child processes.
+    pub mirrored: MirroredResources,
+}
+
+impl Plugin for IsolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Arc::new(self.mirrored.clone()))
+            .init_resource::<IsolatedMods>()
+            .add_system(sync_isolated_mods.exclusive_system());
+    }
+}
+
+/// Exclusive system that updates every isolated mod once, sending the [MirroredResources] to its child process
+/// and applying the resources it sends back. The host waits for each child to finish its update.
+/// A mod whose process exits or does not reply in time is stopped and an error is logged,
+/// the host and other mods keep running.
+pub fn sync_isolated_mods(world: &mut World) {
+    let mirrored = match world.get_resource::<Arc<MirroredResources>>() {
+        Some(mirrored) => mirrored.clone(),
+        None => return,
+    };
+    let mut isolated_mods = match world.remove_resource::<IsolatedMods>() {
+        Some(isolated_mods) => isolated_mods,
+        None => return,
+    };
+
+    for isolated_mod in isolated_mods.mods.iter_mut().filter(|m| m.is_running()) {
+        let frame = mirrored.encode(world);
+        match isolated_mod.step(&frame) {
+            Ok(reply) => mirrored.apply(world, &reply),
+            Err(err) => {
+                let status = isolated_mod.stop();
+                error!(
+                    target: LOG_TARGET,
+                    "Isolated mod '{}' stopped responding and has been stopped! {}{}",
+                    isolated_mod.path.display(),
+                    err,
+                    status.map_or(String::new(), |status| format!(", process {}", status))
+                );
+            }
+        }
+    }
+
+    world.insert_resource(isolated_mods);
+}
+
+/// Start the mod at the specified path in a child process, adding it to the [IsolatedMods] resource.
+pub(crate) fn load_mod_isolated(app: &mut App, mod_path: &str) {
+    match IsolatedMod::spawn(mod_path) {
+        Ok(isolated_mod) => app
+            .world
+            .get_resource_or_insert_with(IsolatedMods::default)
+            .mods
+            .push(isolated_mod),
+        Err(err) => warn!(
+            target: LOG_TARGET,
+            "Failed to start isolated mod from: '{}', {}",
+            mod_path, err
+        ),
+    }
+}
+
+/// Run as the child process of an isolated mod if the process was started as one,
+/// exiting the process once the host closes the child's stdin. Otherwise returns immediately.
+///
+/// Must be called at the very start of the host's `main`, before anything else is set up,
+/// with the same [MirroredResources] the host passes to the [IsolationPlugin].
+/// The child's stdout carries its frames to the host, so anything it prints to stdout is redirected to stderr.
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// # struct Score(u32);
+/// # impl MirroredResource for Score {
+/// #     fn encode(&self) -> Vec<u8> { self.0.to_le_bytes().to_vec() }
+/// #     fn decode(bytes: &[u8]) -> Option<Self> { Some(Score(u32::from_le_bytes(bytes.try_into().ok()?))) }
+/// # }
+/// fn mirrored_resources() -> MirroredResources {
+///     MirroredResources::new().with::<Score>("score")
+/// }
+///
+/// fn main() {
+///     run_isolated_child_if_requested(mirrored_resources());
+///
+///     App::new()
+///         .add_plugin(IsolationPlugin {
+///             mirrored: mirrored_resources(),
+///         })
+///         .insert_resource(Score(0))
+///         .load_mod_isolated("untrusted_plugins/plugin.dll");
+/// }
+/// ```
+pub fn run_isolated_child_if_requested(mirrored: MirroredResources) {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some(ISOLATED_CHILD_ARG) {
+        return;
+    }
+    let mod_path = match args.next() {
+        Some(mod_path) => mod_path,
+        None => {
+            eprintln!("Usage: {} <mod path>", ISOLATED_CHILD_ARG);
+            std::process::exit(2);
+        }
+    };
+    std::process::exit(match run_isolated_child(&mod_path, mirrored) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Isolated mod '{}' failed! {}", mod_path, err);
+            1
+        }
+    });
+}
+
+/// Load the mod into an application of its own, set up with the [CorePlugin](bevy::core::CorePlugin)
+/// and [PluginSystemsPlugin], then update it once for every frame received from the host on stdin,
+/// replying with a frame on stdout.
+fn run_isolated_child(mod_path: &str, mirrored: MirroredResources) -> io::Result<()> {
+    let mut replies = take_stdout_for_frames()?;
+    let stdin = io::stdin();
+    let mut frames = stdin.lock();
+
+    let mut app = App::new();
+    app.add_plugin(bevy::core::CorePlugin)
+        .add_plugin(PluginSystemsPlugin);
+    // The first frame carries the host's state, which the mod may rely on while building.
+    let frame = match read_frame(&mut frames) {
+        Ok(frame) => frame,
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    mirrored.apply(&mut app.world, &frame);
+    // The plugin's code lives in its library, so it must be kept for as long as the app is updated.
+    let _plugin = app
+        .load_mod(mod_path)
+        .ok_or_else(|| io::Error::other("mod could not be loaded"))?;
+
+    loop {
+        app.update();
+        write_frame(&mut replies, &mirrored.encode(&app.world))?;
+        match read_frame(&mut frames) {
+            Ok(frame) => mirrored.apply(&mut app.world, &frame),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Take over the process's stdout for the frames sent to the host, pointing stdout at stderr instead
+/// so that nothing the mod prints can corrupt the frames.
+#[cfg(unix)]
+fn take_stdout_for_frames() -> io::Result<File> {
+    use std::os::unix::io::FromRawFd;
+
+    io::stdout().flush()?;
+    let frames_fd = unsafe { libc::dup(libc::STDOUT_FILENO) };
+    if frames_fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { File::from_raw_fd(frames_fd) })
+}
+
+/// Take over the process's stdout for the frames sent to the host, pointing stdout at stderr instead
+/// so that nothing the mod prints can corrupt the frames.
+#[cfg(windows)]
+fn take_stdout_for_frames() -> io::Result<File> {
+    use std::{ffi::c_void, os::windows::io::FromRawHandle};
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+
+    extern "system" {
+        fn GetStdHandle(std_handle: u32) -> *mut c_void;
+        fn SetStdHandle(std_handle: u32, handle: *mut c_void) -> i32;
+    }
+
+    io::stdout().flush()?;
+    let frames_handle = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) };
+    if frames_handle.is_null() || frames_handle as isize == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // The standard library looks up the stdout handle on every write, so this redirects its stdout.
+    if unsafe { SetStdHandle(STD_OUTPUT_HANDLE, GetStdHandle(STD_ERROR_HANDLE)) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { File::from_raw_handle(frames_handle) })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn take_stdout_for_frames() -> io::Result<File> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "isolated mods are not supported on this platform",
+    ))
+}
+
+/// Write a frame of named resources, each as a length prefixed name followed by length prefixed bytes.
+fn write_frame(writer: &mut impl Write, frame: &[(String, Vec<u8>)]) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    buffer.extend((frame.len() as u32).to_le_bytes());
+    for (name, bytes) in frame {
+        buffer.extend((name.len() as u32).to_le_bytes());
+        buffer.extend(name.as_bytes());
+        buffer.extend((bytes.len() as u32).to_le_bytes());
+        buffer.extend(bytes);
+    }
+    writer.write_all(&buffer)?;
+    writer.flush()
+}
+
+/// Read a frame written by [write_frame].
+fn read_frame(reader: &mut impl Read) -> io::Result<Frame> {
+    fn read_len(reader: &mut impl Read) -> io::Result<usize> {
+        let mut len = [0; 4];
+        reader.read_exact(&mut len)?;
+        match u32::from_le_bytes(len) as usize {
+            len if len > MAX_FRAME_ENTRY_LEN => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "frame entry is too large",
+            )),
+            len => Ok(len),
+        }
+    }
+    fn read_bytes(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0; read_len(reader)?];
+        reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    let count = read_len(reader)?;
+    (0..count)
+        .map(|_| {
+            let name = String::from_utf8(read_bytes(reader)?)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            Ok((name, read_bytes(reader)?))
+        })
+        .collect()
+}
@@ -2,4 +2,63 @@ mod dyn_api;
 pub use dyn_api::*;
 
 mod mod_loader;
-pub use mod_loader::*;
\ No newline at end of file
+pub use mod_loader::*;
+
+mod plugin_info;
+pub use plugin_info::*;
+
+mod host_context;
+pub use host_context::*;
+
+mod plugin_systems;
+pub use plugin_systems::*;
+
+mod error;
+pub use error::*;
+
+mod platform;
+pub use platform::*;
+
+mod search_path;
+
+mod plugin_loader;
+pub use plugin_loader::*;
+
+mod capabilities;
+pub use capabilities::*;
+
+mod report;
+pub use report::*;
+
+mod async_loading;
+pub use async_loading::*;
+
+mod inspect;
+pub use inspect::*;
+
+mod flags;
+pub use flags::*;
+
+mod version;
+pub use version::*;
+
+mod embedded;
+
+mod deferred;
+pub use deferred::*;
+
+mod mod_entities;
+pub use mod_entities::*;
+
+#[cfg(feature = "isolation")]
+mod isolation;
+#[cfg(feature = "isolation")]
+pub use isolation::*;
+#[cfg(feature = "registry")]
+mod registry;
+#[cfg(feature = "registry")]
+pub use registry::*;
+#[cfg(feature = "tokio")]
+mod host_runtime;
+#[cfg(feature = "tokio")]
+pub use host_runtime::*;
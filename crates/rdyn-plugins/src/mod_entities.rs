@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{dyn_api::*, mod_loader::*};
+
+/// Component identifying the loaded mod an entity represents, spawned for each mod in the [ModLoaderData] resource
+/// by the [sync_mod_entities] system, enabled with [ModLoaderPlugin::mod_entities].
+///
+/// The handle refers to its mod by name rather than by index, so it stays valid when the mod is reloaded
+/// or when mods loaded before it are unloaded. Add other components to the entity, such as for a mod menu,
+/// to join them with the mod in queries.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// struct FirstPlugin;
+///
+/// impl Plugin for FirstPlugin {
+///     fn build(&self, _app: &mut App) {}
+///     fn name(&self) -> &str {
+///         "first"
+///     }
+/// }
+///
+/// struct SecondPlugin;
+///
+/// impl Plugin for SecondPlugin {
+///     fn build(&self, _app: &mut App) {}
+///     fn name(&self) -> &str {
+///         "second"
+///     }
+/// }
+///
+/// let mut mod_loader_data = ModLoaderData::<()>::default();
+/// mod_loader_data.push(RustDynPlugin::from_static(Box::new(FirstPlugin), "first").unwrap());
+/// mod_loader_data.push(RustDynPlugin::from_static(Box::new(SecondPlugin), "second").unwrap());
+///
+/// let mut app = App::new();
+/// app.insert_resource(mod_loader_data)
+///     .add_plugin(ModLoaderPlugin { mod_entities: true });
+/// app.update();
+///
+/// let mut handles = app.world.query::<&LoadedModHandle>();
+/// let mut names = handles.iter(&app.world).map(|handle| handle.name()).collect::<Vec<_>>();
+/// names.sort();
+/// assert_eq!(names, vec!["first", "second"]);
+///
+/// app.world.resource_mut::<ModLoaderData>().remove(0);
+/// app.update();
+/// let mut handles = app.world.query::<&LoadedModHandle>();
+/// assert_eq!(handles.iter(&app.world).count(), app.world.resource::<ModLoaderData>().len());
+/// let handle = handles.iter(&app.world).next().unwrap();
+/// let mod_loader_data = app.world.resource::<ModLoaderData>();
+/// assert_eq!(handle.plugin(mod_loader_data).unwrap().name(), "second");
+/// ```
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LoadedModHandle {
+    name: String,
+}
+
+impl LoadedModHandle {
+    /// The name of the mod, as reported by [Plugin::name].
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The mod this handle refers to, `None` if it has been unloaded.
+    /// If several loaded mods have the same name, the first loaded is returned.
+    pub fn plugin<'a>(&self, mod_loader_data: &'a ModLoaderData) -> Option<&'a RustDynPlugin> {
+        mod_loader_data
+            .iter()
+            .find(|plugin| plugin.name() == self.name)
+    }
+}
+
+/// System that spawns an entity with a [LoadedModHandle] for each mod in the [ModLoaderData] resource,
+/// and despawns the entities of mods that are no longer loaded, whenever the resource changes.
+/// Entities of mods that stay loaded, including mods that are reloaded, are kept as they are.
+pub fn sync_mod_entities(
+    mut commands: Commands,
+    mod_loader_data: Option<Res<ModLoaderData>>,
+    handles: Query<(Entity, &LoadedModHandle)>,
+) {
+    if mod_loader_data
+        .as_ref()
+        .is_some_and(|mod_loader_data| !mod_loader_data.is_changed())
+    {
+        return;
+    }
+    let plugins = mod_loader_data.as_deref().map_or(&[][..], |data| &data[..]);
+
+    // Counted per name, as mods with the same name may be loaded depending on the DuplicateModPolicy.
+    let mut loaded = HashMap::<&str, usize>::new();
+    for plugin in plugins {
+        *loaded.entry(plugin.name()).or_default() += 1;
+    }
+    let mut kept = HashMap::<&str, usize>::new();
+    for (entity, handle) in handles.iter() {
+        let kept = kept.entry(handle.name()).or_default();
+        if *kept < loaded.get(handle.name()).copied().unwrap_or_default() {
+            *kept += 1;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for plugin in plugins {
+        match kept.get_mut(plugin.name()) {
+            Some(kept) if *kept > 0 => *kept -= 1,
+            _ => {
+                commands.spawn().insert(LoadedModHandle {
+                    name: plugin.name().to_string(),
+                });
+            }
+        }
+    }
+}
@@ -1,15 +1,42 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    cell::Cell,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
 
-use bevy::prelude::*;
+use bevy::{ecs::system::Resource, prelude::*};
 use std::fs;
 
-use crate::dyn_api::*;
+use crate::{
+    async_loading::*, capabilities::*, dyn_api::*, embedded::*, error::*, flags::*,
+    host_context::*, mod_entities::*, plugin_info::*, plugin_loader::*, plugin_systems::*,
+    report::*, search_path::*,
+};
+
+/// The target of all log output from the mod loader, so that it can be filtered separately from the host's logs,
+/// for example with `RUST_LOG=rdyn_plugins=debug`.
+///
+/// Failures are logged at the warn and error levels. With the "verbose_loading" feature, each mod file
+/// being loaded and unloaded is additionally logged at the debug level.
+pub const LOG_TARGET: &str = "rdyn_plugins";
 
 /// API extension for bevy to allow loading mods into an application.
 pub trait ModLoaderExt {
     /// Loads a mod from a specified file path into an application.
     /// # Example
     /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
     /// let mut app = App::new();
     /// match app.load_mod("plugins/plugin.dll") {
     ///     Some(plugin) => println!("Loaded!"),
@@ -17,22 +44,527 @@ pub trait ModLoaderExt {
     /// }
     /// ```
     fn load_mod(&mut self, mod_path: &str) -> Option<RustDynPlugin>;
+    /// Loads a mod from a specified file path into an application as by "load_mod",
+    /// then clones the resource `R` out of the world, such as a configuration resource the mod inserts while building.
+    /// The resource is `None` if it is not in the world after the mod has been built.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// #[derive(Clone)]
+    /// struct ModConfig {
+    ///     difficulty: u32,
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// if let Some((_plugin, Some(config))) = app.load_mod_and_get::<ModConfig>("plugins/config.dll") {
+    ///     println!("Mod difficulty: {}", config.difficulty);
+    /// }
+    /// ```
+    fn load_mod_and_get<R: Resource + Clone>(
+        &mut self,
+        mod_path: &str,
+    ) -> Option<(RustDynPlugin, Option<R>)>;
+    /// Loads a mod from the bytes of its library into an application, such as a library embedded
+    /// into the host with `include_bytes!`. The name identifies the mod in logs and is stored as its path.
+    /// # Platform support
+    /// Dynamic loaders can only open libraries from files, so the bytes are first written to one.
+    /// - On Linux this is an anonymous in-memory file created with `memfd_create`, nothing is written to disk.
+    ///   The file is kept for the rest of the process, so the library's bytes stay in memory even once it is unloaded.
+    /// - On other platforms this is a file in the temporary directory, removed once the library has been opened.
+    ///   Windows does not allow removing a loaded library, so there the file is left behind.
+    ///
+    /// The library's dependencies are resolved as for any other library, so they must still be found on disk.
+    /// A mod loaded from bytes has no file to reload from, so it cannot be reloaded with "reload_mod".
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// if app.load_mod_from_bytes("plugin.dll", &[]).is_none() {
+    ///     println!("Failed to load!");
+    /// }
+    /// ```
+    fn load_mod_from_bytes(&mut self, name: &str, bytes: &[u8]) -> Option<RustDynPlugin>;
+    /// Load each mod from the name and bytes of its library into an application, in the given order,
+    /// as by "load_mod_from_bytes". Loaded mods are added to any already stored in the [ModLoaderData] resource,
+    /// mods that fail to load are skipped.
+    ///
+    /// Use the "load_embedded_mods!" macro to embed every mod in a directory into the host at compile time
+    /// and load them with this method.
+    fn load_embedded_mods(&mut self, mods: &[(&str, &[u8])]) -> &mut Self;
+    /// Loads a mod from a specified file path into an application,
+    /// adding the specified directories to the search path for the mod's dependent libraries while it loads.
+    /// The previous search path is restored once the mod has loaded.
+    /// # Platform support
+    /// - On Windows the directories are prepended to the `PATH` environment variable,
+    ///   which is searched for dependent DLLs not found next to the host executable.
+    /// - On other platforms the search path cannot be changed after the process starts, so the directories
+    ///   are ignored. Link the mod with an `RPATH` instead, for example `-C link-args=-Wl,-rpath,$ORIGIN/deps`
+    ///   on Linux or `-Wl,-rpath,@loader_path/deps` on macOS.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// # use std::path::PathBuf;
+    /// let mut app = App::new();
+    /// app.load_mod_with_search_dirs("plugins/plugin.dll", &[PathBuf::from("plugins/deps")]);
+    /// ```
+    fn load_mod_with_search_dirs(
+        &mut self,
+        mod_path: &str,
+        search_dirs: &[PathBuf],
+    ) -> Option<RustDynPlugin>;
     /// Load all mods found in a directory into an application.
+    /// Mods are loaded in order of their [load priority](PRIORITY_RDYN_SYM_NAME), highest first,
+    /// and then alphabetically by path.
+    /// Files that are the host executable or the library of an already loaded mod are skipped with a warning.
     /// # Example
     /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
     /// let mut app = App::new();
     /// app.load_mods("plugins");
     /// ```
     fn load_mods(&mut self, mods_directory: &str) -> &mut Self;
+    /// Load all mods found in a directory into an application,
+    /// storing them in a [ModLoaderData] resource tagged with the marker `M`.
+    /// This allows multiple sets of mods to be loaded and queried separately.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// struct CoreMods;
+    /// struct UserMods;
+    ///
+    /// let mut app = App::new();
+    /// app.load_mods_into::<CoreMods>("core_plugins")
+    ///     .load_mods_into::<UserMods>("plugins");
+    /// ```
+    fn load_mods_into<M: 'static>(&mut self, mods_directory: &str) -> &mut Self;
+    /// Load all mods found in a directory as by "load_mods", calling `on_each` after attempting each mod file
+    /// with its path and whether it loaded, such as to update a loading screen as mods are loaded.
+    /// Files that are skipped without being attempted, such as the host executable, are not passed to `on_each`.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods_with_callback("plugins", |path, outcome| match outcome {
+    ///     Ok(()) => println!("Loaded '{}'", path.display()),
+    ///     Err(err) => println!("Failed to load '{}': {}", path.display(), err),
+    /// });
+    /// ```
+    fn load_mods_with_callback(
+        &mut self,
+        mods_directory: &str,
+        on_each: impl FnMut(&Path, &Result<(), ModLoadError>),
+    ) -> &mut Self;
+    /// Load all mods found in several directories into an application, such as base game mods and then user mods,
+    /// storing them in a single [ModLoaderData] resource.
+    /// The directories are loaded in the given order, the mods of each ordered as by "load_mods",
+    /// and the [DuplicateModPolicy] of the [ModLoaderSettings] applies across all of them.
+    /// A directory that cannot be read is skipped with a warning.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.insert_resource(ModLoaderSettings {
+    ///     duplicate_policy: DuplicateModPolicy::SkipDuplicates,
+    ///     ..default()
+    /// })
+    /// .load_mods_all(&["base_plugins", "plugins"]);
+    /// ```
+    fn load_mods_all<P: AsRef<Path>>(&mut self, mods_directories: &[P]) -> &mut Self;
+    /// Load all mods found in a directory relative to the running executable into an application,
+    /// rather than relative to the current working directory.
+    /// See [mods_dir_next_to_exe] for how the directory is resolved.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods_relative_to_exe("plugins");
+    /// ```
+    fn load_mods_relative_to_exe(&mut self, subdirectory: &str) -> &mut Self;
+    /// Load all mods found in the directory named by an environment variable into an application,
+    /// or in the default directory if the variable is not set.
+    /// See [mods_dir_from_env] for how the directory is resolved.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods_env("plugins", "MY_GAME_MODS_DIR");
+    /// ```
+    fn load_mods_env(&mut self, default_dir: &str, env_var: &str) -> &mut Self;
+    /// Start loading all mods found in a directory without blocking, replacing any mods stored in the
+    /// [ModLoaderData] resource once done. Progress is tracked by the [LoadModsTask] resource,
+    /// which is removed once the mods have been built.
+    /// Requires the [AsyncModLoadingPlugin], see [finish_async_mod_loads] for the limitations.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// fn loading_bar(task: Option<Res<LoadModsTask>>, mut progress: EventReader<ModLoadProgress>) {
+    ///     for progress in progress.iter() {
+    ///         println!("Loaded {} of {} mods", progress.opened, progress.total);
+    ///     }
+    ///     if task.is_none() {
+    ///         println!("Finished loading mods!");
+    ///     }
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugin(AsyncModLoadingPlugin)
+    ///     .add_plugin(PluginSystemsPlugin)
+    ///     .add_system(loading_bar)
+    ///     .load_mods_async("plugins");
+    /// ```
+    fn load_mods_async(&mut self, mods_directory: &str) -> &mut Self;
+    /// Load all mods whose paths match a glob pattern into an application,
+    /// such as `mods/*/plugin.dll` to load one mod from each subdirectory of a mods folder.
+    /// Mods are ordered as by "load_mods", see [glob_mod_paths] for how the pattern is matched.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods_glob("mods/*/plugin.dll");
+    /// ```
+    #[cfg(feature = "glob")]
+    fn load_mods_glob(&mut self, pattern: &str) -> &mut Self;
+    /// Load a mod from a specified file path into a child process of the host instead of the application,
+    /// so that a crash in the mod stops only the mod. Requires the [IsolationPlugin](crate::IsolationPlugin)
+    /// and the host calling [run_isolated_child_if_requested](crate::run_isolated_child_if_requested)
+    /// at the start of `main`.
+    ///
+    /// Isolation comes with severe constraints:
+    /// - The mod runs in an application of its own, so only the resources registered in the
+    ///   [MirroredResources](crate::MirroredResources) are shared with the host. Entities, events, assets
+    ///   and any other state are not, and mirrored resources are copied in both directions every frame.
+    /// - The mod is updated once per host frame, and the host waits for each update to finish.
+    /// - If the mod's process crashes or does not reply in time, the mod is stopped and its state is lost.
+    /// - The child is an ordinary process with the same permissions as the host, this is not a security sandbox.
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.add_plugin(IsolationPlugin {
+    ///     mirrored: MirroredResources::new(),
+    /// })
+    /// .load_mod_isolated("untrusted_plugins/plugin.dll");
+    /// ```
+    #[cfg(feature = "isolation")]
+    fn load_mod_isolated(&mut self, mod_path: &str) -> &mut Self;
+    /// Load each mod from a list of file paths into an application, in the given order.
+    /// Loaded mods are added to any already stored in the [ModLoaderData] resource,
+    /// mods that fail to load are skipped.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mod_list(&["plugins/core.dll", "plugins/extra.dll"]);
+    /// ```
+    fn load_mod_list<P: AsRef<Path>>(&mut self, mod_paths: &[P]) -> &mut Self;
+    /// Read the metadata of a mod from a specified file path without building it.
+    /// If a [MetadataCache] resource is present it is used to avoid reopening unchanged mods.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.init_resource::<MetadataCache>();
+    /// if let Some(info) = app.peek_mod("plugins/plugin.dll") {
+    ///     println!("Found mod: {}", info.name);
+    /// }
+    /// ```
+    fn peek_mod(&mut self, mod_path: &str) -> Option<PluginInfo>;
+    /// Register a statically linked plugin to be built in place of a mod that is missing or fails to load.
+    /// After mods are loaded from a directory, any fallback whose name does not match the
+    /// [name](Plugin::name) of a loaded mod is built instead.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// struct BaseGamePlugin;
+    ///
+    /// impl Plugin for BaseGamePlugin {
+    ///     fn build(&self, app: &mut App) {}
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.register_fallback("base_game::BaseGamePlugin", BaseGamePlugin)
+    ///     .load_mods("plugins");
+    /// ```
+    fn register_fallback(&mut self, name: &str, plugin: impl Plugin) -> &mut Self;
+    /// Unload every mod stored in the [ModLoaderData] resource, dropping each plugin.
+    /// Before any plugin is dropped, each mod's [shutdown symbol](SHUTDOWN_RDYN_SYM_NAME) is called
+    /// in reverse load order, so mods can tear down what they set up. The symbol is optional.
+    /// # Limitations
+    /// Bevy does not allow systems to be removed from an application, so any systems,
+    /// resources or other state added by the mods while building remain in the application.
+    /// Systems the mods added to the [PluginSystems] resource are removed along with them,
+    /// any other systems added by the mods must no longer be run once the mods are unloaded.
+    ///
+    /// The mods' libraries are never unloaded and stay mapped for the lifetime of the process,
+    /// as the world keeps pointers into them for every resource and component type the mods registered,
+    /// which are used when those values are replaced or the app is dropped.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods("plugins");
+    /// app.unload_all_mods();
+    /// ```
+    fn unload_all_mods(&mut self) -> &mut Self;
+    /// Unload every mod stored in the [ModLoaderData] resource, then scan the directories or patterns
+    /// they were loaded from again and load the mods found, ordered as by "load_mods".
+    /// Returns [ModLoadError::NoModSources] if the mods were not loaded from a directory or pattern,
+    /// and an error without unloading anything if a directory can no longer be read.
+    /// # Limitations
+    /// As with "unload_all_mods", anything the mods added to the application while building remains.
+    /// Reloading is only safe if the mods add their systems to the [PluginSystems] resource,
+    /// which removes them along with the mods, a warning is logged if the resource is missing.
+    ///
+    /// As with "reload_mod", the old mods' libraries are never unloaded, so each reload leaks them,
+    /// and each mod is loaded from a copy of its file as the platform would otherwise return the already loaded library.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.add_plugin(PluginSystemsPlugin).load_mods("plugins");
+    /// if let Err(err) = app.reload_all_mods() {
+    ///     println!("Could not reload mods: {}", err);
+    /// }
+    /// ```
+    fn reload_all_mods(&mut self) -> Result<(), ModLoadError>;
+    /// Reload a single mod stored in the [ModLoaderData] resource by its plugin name, from the file it was loaded from,
+    /// handing its state over to the reloaded plugin. The mod keeps its place in the load order.
+    ///
+    /// Before the old plugin is unloaded its [snapshot symbol](SNAPSHOT_RDYN_SYM_NAME) saves its state,
+    /// which is passed to the reloaded plugin's [restore symbol](RESTORE_RDYN_SYM_NAME) once it has been built.
+    /// Both symbols are optional, a plugin without them is reloaded without its state.
+    ///
+    /// If the file's modified time is the same as when the mod was loaded, nothing is done and
+    /// [ReloadOutcome::Unchanged] is returned, so reloads can be triggered freely without reloading unchanged mods.
+    /// A mod whose modified time could not be read is always reloaded.
+    ///
+    /// Returns [ModLoadError::ModNotLoaded] if no mod has the name. Otherwise the old plugin is always dropped,
+    /// if the reloaded plugin fails to load the error is returned and the mod is no longer stored.
+    /// # Limitations
+    /// As with "reload_all_mods", the mod must add its systems to the [PluginSystems] resource to be safely reloaded.
+    ///
+    /// The old plugin's library is never unloaded, as the world keeps pointers into it for every resource
+    /// and component type the mod registered, so each reload leaks a library. The file is copied to the
+    /// temporary directory and the copy is loaded, as the platform would otherwise return the already loaded library.
+    /// This is intended for iterating on mods during development.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.add_plugin(PluginSystemsPlugin).load_mods("plugins");
+    /// match app.reload_mod("my_mod::MyModPlugin") {
+    ///     Ok(ReloadOutcome::Reloaded) => println!("Reloaded mod!"),
+    ///     Ok(ReloadOutcome::Unchanged) => println!("Mod has not changed"),
+    ///     Err(err) => println!("Could not reload mod: {}", err),
+    /// }
+    /// ```
+    fn reload_mod(&mut self, plugin_name: &str) -> Result<ReloadOutcome, ModLoadError>;
 }
 
 /// Stores all the loaded plugins loaded via the "load_mods" extension method.
-#[derive(Default)]
-pub struct ModLoaderData {
+/// The marker type `M` distinguishes separate sets of loaded mods,
+/// plugins loaded via "load_mods" use the default unit marker.
+/// Mods that failed to load are recorded along with the reason, see [ModLoaderData::failures].
+pub struct ModLoaderData<M = ()> {
     pub loaded_plugins: Vec<RustDynPlugin>,
+    failures: Vec<(PathBuf, ModLoadError)>,
+    sources: Vec<ModSource>,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M> Default for ModLoaderData<M> {
+    fn default() -> Self {
+        Self {
+            loaded_plugins: Vec::new(),
+            failures: Vec::new(),
+            sources: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> Debug for ModLoaderData<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModLoaderData")
+            .field("loaded_plugins", &self.loaded_plugins)
+            .field("failures", &self.failures)
+            .field("sources", &self.sources)
+            .finish()
+    }
+}
+
+impl<M> ModLoaderData<M> {
+    /// The path of every mod that failed to load into this set of mods, along with the reason it failed.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods("plugins");
+    /// for (path, err) in app.world.resource::<ModLoaderData>().failures() {
+    ///     println!("Could not load '{}': {}", path.display(), err);
+    /// }
+    /// ```
+    pub fn failures(&self) -> &[(PathBuf, ModLoadError)] {
+        &self.failures
+    }
+
+    /// The loaded plugins that were fully built, see [RustDynPlugin::build_succeeded].
+    pub fn active_plugins(&self) -> impl Iterator<Item = &RustDynPlugin> {
+        self.loaded_plugins
+            .iter()
+            .filter(|plugin| plugin.build_succeeded)
+    }
+
+    /// The loaded plugins whose library is loaded but whose build did not succeed,
+    /// see [RustDynPlugin::build_succeeded].
+    pub fn failed_builds(&self) -> impl Iterator<Item = &RustDynPlugin> {
+        self.loaded_plugins
+            .iter()
+            .filter(|plugin| !plugin.build_succeeded)
+    }
+
+    /// The name of every loaded plugin in load order, as reported by [Plugin::name].
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// struct FirstPlugin;
+    ///
+    /// impl Plugin for FirstPlugin {
+    ///     fn build(&self, _app: &mut App) {}
+    ///     fn name(&self) -> &str {
+    ///         "first"
+    ///     }
+    /// }
+    ///
+    /// struct SecondPlugin;
+    ///
+    /// impl Plugin for SecondPlugin {
+    ///     fn build(&self, _app: &mut App) {}
+    ///     fn name(&self) -> &str {
+    ///         "second"
+    ///     }
+    /// }
+    ///
+    /// let mut mod_loader_data = ModLoaderData::<()>::default();
+    /// mod_loader_data.push(RustDynPlugin::from_static(Box::new(FirstPlugin), "first").unwrap());
+    /// mod_loader_data.push(RustDynPlugin::from_static(Box::new(SecondPlugin), "second").unwrap());
+    /// assert_eq!(mod_loader_data.names(), vec!["first", "second"]);
+    /// assert_eq!((&mod_loader_data).into_iter().count(), 2);
+    /// ```
+    pub fn names(&self) -> Vec<&str> {
+        self.loaded_plugins
+            .iter()
+            .map(|plugin| plugin.name())
+            .collect()
+    }
+
+    /// The name and version of every loaded plugin in load order, see [RustDynPlugin::version].
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods("plugins");
+    /// for (name, version) in app.world.resource::<ModLoaderData>().versions() {
+    ///     println!("{} v{} installed", name, version.unwrap_or("unknown"));
+    /// }
+    /// ```
+    pub fn versions(&self) -> Vec<(&str, Option<&str>)> {
+        self.loaded_plugins
+            .iter()
+            .map(|plugin| (plugin.name(), plugin.version()))
+            .collect()
+    }
+
+    /// Check that the library of every loaded plugin is still loaded, returning each plugin's name
+    /// and whether it passed the check, see [RustDynPlugin::verify] for what is checked and its limitations.
+    /// Intended as a periodic sanity check in long running sessions that reload and unload mods.
+    ///
+    /// The name of a plugin that fails the check is not read, as that would call into its library,
+    /// its path is returned instead.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods("plugins");
+    /// for (name, ok) in app.world.resource::<ModLoaderData>().verify() {
+    ///     if !ok {
+    ///         println!("The library of mod '{}' is no longer loaded!", name);
+    ///     }
+    /// }
+    /// ```
+    pub fn verify(&self) -> Vec<(String, bool)> {
+        self.loaded_plugins
+            .iter()
+            .map(|plugin| match plugin.verify() {
+                true => (plugin.name().to_string(), true),
+                false => (plugin.path.display().to_string(), false),
+            })
+            .collect()
+    }
+
+    /// Query the operating system for the total number of bytes of address space
+    /// mapped from the libraries of all loaded plugins.
+    /// # Platform support
+    /// See [RustDynPlugin::mapped_bytes], returns `None` if any library's mappings could not be read.
+    pub fn total_mapped_bytes(&self) -> Option<usize> {
+        self.loaded_plugins
+            .iter()
+            .map(RustDynPlugin::mapped_bytes)
+            .sum()
+    }
+
+    /// Forget the library of every loaded plugin so that the libraries stay loaded for the lifetime
+    /// of the process, returning the plugins that were loaded from them.
+    /// # Use case
+    /// Used by programs that create and drop multiple apps in one process, such as test runners or editors,
+    /// where code from a library may still be running after the app is dropped, for example on a thread
+    /// spawned by the plugin. Take the resource out of the app and leak it right before the app is dropped.
+    /// # Memory
+    /// The libraries are never unloaded, so their code, data and any memory they allocated stay mapped
+    /// until the process exits. Loading the same mods again in another app maps them again
+    /// unless the platform reuses the already loaded library.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// let mut app = App::new();
+    /// app.load_mods("plugins");
+    /// if let Some(mod_loader_data) = app.world.remove_resource::<ModLoaderData>() {
+    ///     mod_loader_data.leak_all();
+    /// }
+    /// drop(app);
+    /// ```
+    pub fn leak_all(self) -> Vec<Box<dyn Plugin>> {
+        self.loaded_plugins
+            .into_iter()
+            .map(RustDynPlugin::forget_library)
+            .collect()
+    }
 }
 
-impl Deref for ModLoaderData {
+impl<M> Deref for ModLoaderData<M> {
     type Target = Vec<RustDynPlugin>;
 
     fn deref(&self) -> &Self::Target {
@@ -40,54 +572,1380 @@ impl Deref for ModLoaderData {
     }
 }
 
-impl DerefMut for ModLoaderData {
+impl<M> DerefMut for ModLoaderData<M> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.loaded_plugins
     }
 }
 
+impl<M> IntoIterator for ModLoaderData<M> {
+    type Item = RustDynPlugin;
+    type IntoIter = std::vec::IntoIter<RustDynPlugin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.loaded_plugins.into_iter()
+    }
+}
+
+impl<'a, M> IntoIterator for &'a ModLoaderData<M> {
+    type Item = &'a RustDynPlugin;
+    type IntoIter = std::slice::Iter<'a, RustDynPlugin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.loaded_plugins.iter()
+    }
+}
+
+/// Stores the statically linked fallback plugins registered via the "register_fallback" extension method,
+/// keyed by the name of the mod they stand in for.
+#[derive(Default)]
+pub struct ModFallbacks {
+    pub fallbacks: HashMap<String, Box<dyn Plugin>>,
+}
+
+/// Settings for loading mods into an application, insert as a resource to change the defaults.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// # use std::time::Duration;
+/// let mut app = App::new();
+/// app.insert_resource(ModLoaderSettings {
+///     build_timeout: Some(Duration::from_secs(5)),
+///     ..default()
+/// })
+/// .load_mods("plugins");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ModLoaderSettings {
+    /// Log an error identifying a mod whose build has not returned after this long, disabled if `None`.
+    /// The build is not interrupted, as it cannot be safely stopped while it has access to the app,
+    /// so this only makes a hanging mod attributable.
+    pub build_timeout: Option<Duration>,
+    /// Do not warn when a mod was compiled with a different build profile (debug or release) than the host,
+    /// see [RustDynPlugin::build_profile].
+    pub ignore_profile_mismatch: bool,
+    /// The capabilities granted to mods, mods declaring any other capability are skipped before they are built.
+    /// Skipped mods are recorded in [ModLoaderData::failures] with [ModLoadError::CapabilityDenied],
+    /// the capabilities of loaded mods were all granted, see [RustDynPlugin::capabilities].
+    pub capability_policy: CapabilityPolicy,
+    /// The version of the host's API mods must have been compiled against, not checked if `None`.
+    /// Mods reporting a different version, or not reporting one, are skipped before they are built
+    /// and recorded with [ModLoadError::HostApiMismatch], see [RustDynPlugin::host_api_version].
+    pub expected_host_api: Option<u32>,
+    /// The flags provided by the host, mods requiring any other flag are skipped before they are built.
+    /// Skipped mods are recorded in [ModLoaderData::failures] with [ModLoadError::MissingFlags],
+    /// see [RustDynPlugin::required_flags].
+    pub host_flags: HashSet<String>,
+    /// What to do with a mod whose plugin has the same name as an already loaded mod.
+    pub duplicate_policy: DuplicateModPolicy,
+    /// Log an error when a mod replaces the app runner during build and restore the host's runner.
+    /// A best-effort check for mods that add their own windowing or event loop plugin, such as `WinitPlugin`.
+    pub guard_runner: bool,
+    /// Retain the libraries of loaded mods in the process-global [PluginRegistry],
+    /// so that apps created later reuse them instead of opening them again.
+    /// See [PluginRegistry] for the lifetime and memory tradeoffs.
+    #[cfg(feature = "registry")]
+    pub use_registry: bool,
+}
+
+/// What to do with a mod whose plugin has the same name as a mod already loaded into the same [ModLoaderData].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateModPolicy {
+    /// Load every mod, even if another mod has the same name.
+    #[default]
+    LoadAll,
+    /// Skip a mod whose name is already loaded before it is built, keeping the mod loaded first.
+    /// Skipped mods are recorded in [ModLoaderData::failures] with [ModLoadError::DuplicateName].
+    SkipDuplicates,
+}
+
+/// Sets up the optional systems of the mod loader, none are added by default.
+/// Mods can be loaded without this plugin.
+#[derive(Debug, Default, Clone)]
+pub struct ModLoaderPlugin {
+    /// Mirror the [ModLoaderData] resource with an entity per loaded mod, see [LoadedModHandle].
+    pub mod_entities: bool,
+}
+
+impl Plugin for ModLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        if self.mod_entities {
+            app.add_system(sync_mod_entities);
+        }
+    }
+}
+
+/// The outcome of reloading a mod with the "reload_mod" extension method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    /// The mod was unloaded and loaded again from its file.
+    Reloaded,
+    /// The mod's file has not changed since it was loaded, so the mod was left as it is.
+    Unchanged,
+}
+
+/// Watches a dynamic plugin's build from a separate thread,
+/// logging an error if it has not finished before the timeout elapses.
+struct BuildWatchdog {
+    finished: mpsc::Sender<()>,
+}
+
+impl BuildWatchdog {
+    fn start(plugin: &RustDynPlugin, timeout: Duration) -> Self {
+        let (finished, finished_receiver) = mpsc::channel::<()>();
+        let plugin_name = plugin.name().to_string();
+        let plugin_path = plugin.path.clone();
+        std::thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = finished_receiver.recv_timeout(timeout) {
+                error!(
+                    target: LOG_TARGET,
+                    "Mod '{}' from '{}' has not finished building after {:?}, it may be stuck.",
+                    plugin_name,
+                    plugin_path.display(),
+                    timeout
+                );
+            }
+        });
+        Self { finished }
+    }
+
+    fn finish(self) {
+        // Dropping the sender wakes the watchdog thread, which then exits.
+        drop(self.finished);
+    }
+}
+
+/// Swaps out the host's runner while a dynamic plugin builds, so that a plugin
+/// which adds its own windowing or event loop plugin (such as `WinitPlugin`),
+/// and with it a second runner, can be reported and undone rather than
+/// clashing with the host's event loop.
+struct RunnerGuard {
+    host_runner: Box<dyn Fn(App)>,
+    replaced: Rc<Cell<bool>>,
+}
+
+/// Captured by the placeholder runner, flags the runner as replaced when it is dropped.
+struct RunnerSentinel(Rc<Cell<bool>>);
+
+impl Drop for RunnerSentinel {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+impl RunnerGuard {
+    fn install(app: &mut App) -> Self {
+        let replaced = Rc::new(Cell::new(false));
+        let sentinel = RunnerSentinel(replaced.clone());
+        let host_runner = std::mem::replace(
+            &mut app.runner,
+            Box::new(move |_| {
+                let _ = &sentinel;
+            }),
+        );
+        Self {
+            host_runner,
+            replaced,
+        }
+    }
+
+    fn restore(self, app: &mut App, plugin_name: &str) {
+        if self.replaced.get() {
+            error!(
+                target: LOG_TARGET,
+                "Mod '{}' replaced the app runner during build, it most likely added its own windowing or event loop plugin. \
+                 The host's runner has been restored, remove the plugin from the mod to resolve this.",
+                plugin_name
+            );
+        }
+        app.runner = self.host_runner;
+    }
+}
+
 impl ModLoaderExt for App {
     fn load_mod(&mut self, mod_path: &str) -> Option<RustDynPlugin> {
-        #[cfg(feature = "verbose_loading")]
-        info!("Loading mod from: '{}'", mod_path);
+        try_load_mod(self, mod_path).ok()
+    }
+
+    fn load_mod_and_get<R: Resource + Clone>(
+        &mut self,
+        mod_path: &str,
+    ) -> Option<(RustDynPlugin, Option<R>)> {
+        let plugin = self.load_mod(mod_path)?;
+        let resource = self.world.get_resource::<R>().cloned();
+        Some((plugin, resource))
+    }
+
+    fn load_mod_from_bytes(&mut self, name: &str, bytes: &[u8]) -> Option<RustDynPlugin> {
+        let loaded = open_mod_from_bytes(self, name, bytes);
+        build_loaded_mod(self, name, loaded).ok()
+    }
+
+    fn load_embedded_mods(&mut self, mods: &[(&str, &[u8])]) -> &mut Self {
+        let names: Vec<&str> = mods.iter().map(|(name, _)| *name).collect();
+        let open = |app: &mut App, name: &str| {
+            let (_, bytes) = mods
+                .iter()
+                .find(|(mod_name, _)| *mod_name == name)
+                .expect("every name has embedded bytes");
+            open_mod_from_bytes(app, name, bytes)
+        };
+        load_mod_list_with::<(), _>(self, &names, open, |_, _| {});
+        self
+    }
+
+    fn load_mod_with_search_dirs(
+        &mut self,
+        mod_path: &str,
+        search_dirs: &[PathBuf],
+    ) -> Option<RustDynPlugin> {
+        let search_path_guard = SearchPathGuard::install(search_dirs);
+        let plugin = self.load_mod(mod_path);
+        search_path_guard.restore();
+        plugin
+    }
+
+    fn load_mods(&mut self, mods_directory: &str) -> &mut Self {
+        self.load_mods_into::<()>(mods_directory)
+    }
+
+    fn load_mods_into<M: 'static>(&mut self, mods_directory: &str) -> &mut Self {
+        let plugin_paths = scan_mods_directory(mods_directory).unwrap_or_else(|err| {
+            warn_unreadable_mods_directory(mods_directory, &err);
+            Vec::new()
+        });
+
+        let sources = vec![ModSource::Directory(mods_directory.to_string())];
+        load_found_mods_into::<M>(self, plugin_paths, sources);
+        self
+    }
+
+    fn load_mods_with_callback(
+        &mut self,
+        mods_directory: &str,
+        mut on_each: impl FnMut(&Path, &Result<(), ModLoadError>),
+    ) -> &mut Self {
+        let mut plugin_paths = scan_mods_directory(mods_directory).unwrap_or_else(|err| {
+            warn_unreadable_mods_directory(mods_directory, &err);
+            Vec::new()
+        });
+        sort_by_priority(self, &mut plugin_paths);
 
-        match RustDynPlugin::load_from(mod_path) {
-            Some(plugin) => {
-                plugin.build(self);
-                #[cfg(feature = "verbose_loading")]
-                info!("Loaded mod: {:?}", plugin);
-                Some(plugin)
+        let sources = vec![ModSource::Directory(mods_directory.to_string())];
+        load_sorted_mods_with::<()>(self, &plugin_paths, sources, open_mod, &mut on_each);
+        self
+    }
+
+    fn load_mods_all<P: AsRef<Path>>(&mut self, mods_directories: &[P]) -> &mut Self {
+        let mut plugin_paths = Vec::new();
+        let mut sources = Vec::new();
+        for mods_directory in mods_directories {
+            let mods_directory = mods_directory.as_ref();
+            let mods_directory = match mods_directory.to_str() {
+                Some(mods_directory) => mods_directory,
+                None => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Could not find mods folder! Invalid path: '{:?}'",
+                        mods_directory
+                    );
+                    continue;
+                }
+            };
+            match scan_mods_directory(mods_directory) {
+                Ok(mut directory_paths) => {
+                    sort_by_priority(self, &mut directory_paths);
+                    plugin_paths.extend(directory_paths);
+                }
+                Err(err) => warn_unreadable_mods_directory(mods_directory, &err),
             }
+            sources.push(ModSource::Directory(mods_directory.to_string()));
+        }
+
+        load_sorted_mods_with::<()>(self, &plugin_paths, sources, open_mod, |_, _| {});
+        self
+    }
+
+    #[cfg(feature = "glob")]
+    fn load_mods_glob(&mut self, pattern: &str) -> &mut Self {
+        let plugin_paths = glob_mod_paths(pattern);
+        let sources = vec![ModSource::Glob(pattern.to_string())];
+        load_found_mods_into::<()>(self, plugin_paths, sources);
+        self
+    }
+
+    #[cfg(feature = "isolation")]
+    fn load_mod_isolated(&mut self, mod_path: &str) -> &mut Self {
+        crate::isolation::load_mod_isolated(self, mod_path);
+        self
+    }
+
+    fn reload_all_mods(&mut self) -> Result<(), ModLoadError> {
+        let sources = match self.world.get_resource::<ModLoaderData>() {
+            Some(mod_loader_data) if !mod_loader_data.sources.is_empty() => {
+                mod_loader_data.sources.clone()
+            }
+            _ => return Err(ModLoadError::NoModSources),
+        };
+
+        // Scan before unloading, so that the current mods are kept if a source can no longer be read.
+        // Each source is ordered separately, so that mods from earlier sources are loaded first.
+        let mut plugin_paths = Vec::new();
+        for source in sources.iter() {
+            let mut source_paths = source.scan()?;
+            sort_by_priority(self, &mut source_paths);
+            plugin_paths.extend(source_paths);
+        }
+
+        warn_if_no_plugin_systems(self);
+        self.unload_all_mods();
+        load_sorted_mods_with::<()>(self, &plugin_paths, sources, open_mod_copy, |_, _| {});
+        Ok(())
+    }
+
+    fn reload_mod(&mut self, plugin_name: &str) -> Result<ReloadOutcome, ModLoadError> {
+        let (index, old_plugin) = self
+            .world
+            .get_resource::<ModLoaderData>()
+            .and_then(|mod_loader_data| {
+                mod_loader_data
+                    .loaded_plugins
+                    .iter()
+                    .enumerate()
+                    .find(|(_, plugin)| plugin.name() == plugin_name)
+            })
+            .ok_or_else(|| ModLoadError::ModNotLoaded(plugin_name.to_string()))?;
+        let modified = file_modified(&old_plugin.path);
+        if modified.is_some() && modified == old_plugin.modified {
+            return Ok(ReloadOutcome::Unchanged);
+        }
+
+        warn_if_no_plugin_systems(self);
+        let old_plugin = self
+            .world
+            .resource_mut::<ModLoaderData>()
+            .loaded_plugins
+            .remove(index);
+        let state = old_plugin.snapshot(&mut self.world);
+        if let Some(mut plugin_systems) = self.world.get_resource_mut::<PluginSystems>() {
+            plugin_systems.clear_plugin(old_plugin.name());
+        }
+        let mod_path = old_plugin.path.clone();
+        // The plugin itself can be dropped but its library must stay loaded, see the limitations above.
+        old_plugin.forget_library();
+
+        let mod_path_str = mod_path.to_string_lossy();
+        #[cfg(feature = "trace")]
+        let _load_span = info_span!(target: LOG_TARGET, "load_mod", path = %mod_path_str).entered();
+        let loaded = open_mod_copy(self, &mod_path_str);
+        let mut result = build_loaded_mod(self, &mod_path_str, loaded);
+        if let Ok(plugin) = &mut result {
+            plugin.modified = modified;
+        }
+        if let (Ok(plugin), Some(state)) = (&result, &state) {
+            if !plugin.restore(&mut self.world, state) {
+                warn!(
+                    target: LOG_TARGET,
+                    "Mod '{}' from '{}' does not export a restore symbol, the state saved before reloading is discarded",
+                    plugin.name(),
+                    mod_path.display()
+                );
+            }
+        }
+
+        let mut mod_loader_data = self.world.resource_mut::<ModLoaderData>();
+        let result = result.map(|plugin| mod_loader_data.loaded_plugins.insert(index, plugin));
+        let report = ModLoadReport::from_data(&*mod_loader_data);
+        self.insert_resource(report);
+        result.map(|()| ReloadOutcome::Reloaded)
+    }
+
+    fn load_mods_async(&mut self, mods_directory: &str) -> &mut Self {
+        start_load_mods_async(self, mods_directory);
+        self
+    }
+
+    fn load_mods_env(&mut self, default_dir: &str, env_var: &str) -> &mut Self {
+        let mods_directory = mods_dir_from_env(default_dir, env_var);
+        self.load_mods(&mods_directory)
+    }
+
+    fn load_mods_relative_to_exe(&mut self, subdirectory: &str) -> &mut Self {
+        let mods_directory = mods_dir_next_to_exe(subdirectory);
+        match mods_directory.to_str() {
+            Some(mods_directory) => self.load_mods(mods_directory),
             None => {
-                #[cfg(feature = "verbose_loading")]
-                warn!("Failed to load plugin from: '{}'", mod_path);
-                None
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not find mods folder! Invalid path: '{:?}'",
+                    mods_directory
+                );
+                self
             }
         }
     }
 
-    fn load_mods(&mut self, mods_directory: &str) -> &mut Self {
-        let mut mod_loader_data = ModLoaderData::default();
-
-        match fs::read_dir(mods_directory) {
-            Err(err) => warn!("Could not find mods folder! {}", err),
-            Ok(plugins) => plugins
-                .flatten()
-                .filter(|p| p.file_type().map_or(false, |f| f.is_file()))
-                .for_each(|plugin| match plugin.path().to_str() {
-                    None => {
-                        #[cfg(feature = "verbose_loading")]
-                        warn!("Failed to get path of plugin from: '{:?}'", plugin);
-                    }
-                    Some(plugin_path) => {
-                        if let Some(plugin) = self.load_mod(plugin_path) {
-                            mod_loader_data.loaded_plugins.push(plugin);
-                        }
-                    }
-                }),
-        }
-        
-        self.insert_resource(mod_loader_data);
+    fn load_mod_list<P: AsRef<Path>>(&mut self, mod_paths: &[P]) -> &mut Self {
+        load_mod_list_into::<(), _>(self, mod_paths);
+        self
+    }
+
+    fn peek_mod(&mut self, mod_path: &str) -> Option<PluginInfo> {
+        let loader = plugin_loader(self);
+        let host_context = self.world.get_resource::<HostContext>().cloned();
+        match self.world.get_resource_mut::<MetadataCache>() {
+            Some(mut cache) => cache.peek_with_loader(mod_path, &*loader, host_context.as_ref()),
+            None => loader.info(mod_path, host_context.as_ref()),
+        }
+    }
+
+    fn unload_all_mods(&mut self) -> &mut Self {
+        if let Some(mut mod_loader_data) = self.world.get_resource_mut::<ModLoaderData>() {
+            #[cfg(feature = "verbose_loading")]
+            debug!(target: LOG_TARGET, "Unloading {} mods", mod_loader_data.len());
+            let unloaded_plugins = std::mem::take(&mut mod_loader_data.loaded_plugins);
+            let report = ModLoadReport::from_data(&*mod_loader_data);
+            self.insert_resource(report);
+
+            for plugin in unloaded_plugins.iter().rev() {
+                plugin.shutdown(&mut self.world);
+            }
+
+            if let Some(mut plugin_systems) = self.world.get_resource_mut::<PluginSystems>() {
+                for plugin in unloaded_plugins.iter() {
+                    plugin_systems.clear_plugin(plugin.name());
+                }
+            }
+            // The plugins can be dropped but their libraries must stay loaded, see the limitations above.
+            for plugin in unloaded_plugins {
+                plugin.forget_library();
+            }
+        }
+        self
+    }
+
+    fn register_fallback(&mut self, name: &str, plugin: impl Plugin) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(ModFallbacks::default)
+            .fallbacks
+            .insert(name.to_string(), Box::new(plugin));
         self
     }
 }
+
+/// Resolve a mods directory relative to the directory containing the running executable,
+/// so that mods are found regardless of the working directory the program was started from.
+/// If the executable's path cannot be determined, the directory is resolved relative to
+/// the current working directory instead and a warning is logged.
+pub fn mods_dir_next_to_exe(subdirectory: &str) -> PathBuf {
+    match std::env::current_exe() {
+        Ok(exe_path) => match exe_path.parent() {
+            Some(exe_directory) => exe_directory.join(subdirectory),
+            None => PathBuf::from(subdirectory),
+        },
+        Err(err) => {
+            warn!(
+                target: LOG_TARGET,
+                "Could not find the executable's directory, resolving mods folder from the working directory instead! {}",
+                err
+            );
+            PathBuf::from(subdirectory)
+        }
+    }
+}
+
+/// Load a mod from a specified file path and build it into an application,
+/// logging a warning if it fails to load.
+fn try_load_mod(app: &mut App, mod_path: &str) -> Result<RustDynPlugin, ModLoadError> {
+    #[cfg(feature = "trace")]
+    let _load_span = info_span!(target: LOG_TARGET, "load_mod", path = %mod_path).entered();
+    let loaded = open_mod(app, mod_path);
+    build_loaded_mod(app, mod_path, loaded)
+}
+
+/// Load a mod from the bytes of its library with the application's [PluginLoader], without building it.
+/// The plugin's path is set to the mod's name, as the file it was opened from is removed.
+fn open_mod_from_bytes(
+    app: &mut App,
+    name: &str,
+    bytes: &[u8],
+) -> Result<RustDynPlugin, ModLoadError> {
+    let embedded_file = EmbeddedModFile::write(name, bytes)?;
+    let mut plugin = open_mod(app, &embedded_file.path().to_string_lossy())?;
+    plugin.path = PathBuf::from(name);
+    plugin.modified = None;
+    Ok(plugin)
+}
+
+/// Load a mod from a specified file path with the application's [PluginLoader], without building it.
+fn open_mod(app: &mut App, mod_path: &str) -> Result<RustDynPlugin, ModLoadError> {
+    #[cfg(feature = "verbose_loading")]
+    debug!(target: LOG_TARGET, "Loading mod from: '{}'", mod_path);
+
+    #[cfg(feature = "registry")]
+    if app
+        .world
+        .get_resource::<ModLoaderSettings>()
+        .is_some_and(|settings| settings.use_registry)
+    {
+        if let Err(err) = crate::PluginRegistry::global().retain(mod_path) {
+            warn!(
+                target: LOG_TARGET,
+                "Could not retain mod '{}' in the plugin registry, {}",
+                mod_path,
+                err
+            );
+        }
+    }
+
+    let host_context = app.world.get_resource::<HostContext>().cloned();
+    plugin_loader(app).load(mod_path, host_context.as_ref())
+}
+
+/// Load a mod from a copy of its file with the application's [PluginLoader], without building it,
+/// as the platform would otherwise return the library of an unloaded mod from the same path, which stays loaded.
+/// The plugin's path is set to the original file, which is loaded instead if it could not be copied.
+fn open_mod_copy(app: &mut App, mod_path: &str) -> Result<RustDynPlugin, ModLoadError> {
+    let mod_path = Path::new(mod_path);
+    let copy_path = copy_for_reload(mod_path).unwrap_or_else(|err| {
+        warn!(
+            target: LOG_TARGET,
+            "Could not copy mod '{}' for reloading, loading it from its original path! {}",
+            mod_path.display(),
+            err
+        );
+        mod_path.to_path_buf()
+    });
+    let result = open_mod(app, &copy_path.to_string_lossy());
+    if copy_path != mod_path {
+        // The copy stays mapped while loaded, where platforms allow it to be removed.
+        let _ = std::fs::remove_file(&copy_path);
+    }
+    result.map(|mut plugin| {
+        plugin.path = mod_path.to_path_buf();
+        plugin.modified = file_modified(mod_path);
+        plugin
+    })
+}
+
+/// Check and build a mod that has been loaded from the specified path into an application,
+/// logging a warning if it failed to load or is skipped.
+fn build_loaded_mod(
+    app: &mut App,
+    mod_path: &str,
+    loaded: Result<RustDynPlugin, ModLoadError>,
+) -> Result<RustDynPlugin, ModLoadError> {
+    match loaded {
+        Ok(mut plugin) => {
+            check_loaded_mod(app, &plugin)?;
+            build_checked_mod(app, &mut plugin);
+            Ok(plugin)
+        }
+        Err(err) => {
+            warn!(target: LOG_TARGET, "Failed to load plugin from: '{}', {}", mod_path, err);
+            Err(err)
+        }
+    }
+}
+
+/// Check a loaded mod against the application's [ModLoaderSettings],
+/// logging a warning if it is skipped.
+pub(crate) fn check_loaded_mod(app: &App, plugin: &RustDynPlugin) -> Result<(), ModLoadError> {
+    let settings = app
+        .world
+        .get_resource::<ModLoaderSettings>()
+        .cloned()
+        .unwrap_or_default();
+    if !settings.ignore_profile_mismatch {
+        warn_if_profile_mismatch(plugin);
+    }
+    check_host_api(plugin, settings.expected_host_api)
+        .and_then(|_| check_required_flags(&plugin.required_flags(), &settings.host_flags))
+        .and_then(|_| settings.capability_policy.check(&plugin.capabilities()))
+        .map_err(|err| {
+            warn!(
+                target: LOG_TARGET,
+                "Skipping mod '{}' from '{}', {}",
+                plugin.name(),
+                plugin.path.display(),
+                err
+            );
+            err
+        })
+}
+
+/// Build a mod that passed [check_loaded_mod] into an application, watched by the [BuildWatchdog]
+/// if the [ModLoaderSettings] set a build timeout.
+pub(crate) fn build_checked_mod(app: &mut App, plugin: &mut RustDynPlugin) {
+    let (build_timeout, guard_runner) = app
+        .world
+        .get_resource::<ModLoaderSettings>()
+        .map_or((None, false), |settings| {
+            (settings.build_timeout, settings.guard_runner)
+        });
+
+    #[cfg(feature = "trace")]
+    let _build_span = info_span!(target: LOG_TARGET, "build", plugin = plugin.name()).entered();
+    let watchdog = build_timeout.map(|timeout| BuildWatchdog::start(plugin, timeout));
+    let runner_guard = guard_runner.then(|| RunnerGuard::install(app));
+    plugin.build_succeeded = build_plugin(app, plugin);
+    if let Some(runner_guard) = runner_guard {
+        runner_guard.restore(app, plugin.name());
+    }
+    if let Some(watchdog) = watchdog {
+        watchdog.finish();
+    }
+    #[cfg(feature = "verbose_loading")]
+    debug!(target: LOG_TARGET, "Loaded mod: {:?}", plugin);
+}
+
+/// Check that a plugin was compiled against the host API version expected by the host, if any.
+fn check_host_api(
+    plugin: &RustDynPlugin,
+    expected_host_api: Option<u32>,
+) -> Result<(), ModLoadError> {
+    match expected_host_api {
+        Some(expected) if plugin.host_api_version() != Some(expected) => {
+            Err(ModLoadError::HostApiMismatch {
+                expected,
+                found: plugin.host_api_version(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Copy a mod's file to a unique path in the temporary directory, so that it can be loaded
+/// while the library previously loaded from the original path stays loaded.
+fn copy_for_reload(mod_path: &Path) -> std::io::Result<PathBuf> {
+    static RELOAD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = mod_path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "mod path has no file name",
+        )
+    })?;
+    let reload_path = std::env::temp_dir().join(format!(
+        "rdyn-reload-{}-{}-{}",
+        std::process::id(),
+        RELOAD_COUNT.fetch_add(1, Ordering::Relaxed),
+        file_name.to_string_lossy()
+    ));
+    std::fs::copy(mod_path, &reload_path)?;
+    Ok(reload_path)
+}
+
+/// Warn if mods are reloaded without a [PluginSystems] resource to remove the systems of the unloaded mods.
+fn warn_if_no_plugin_systems(app: &App) {
+    if !app.world.contains_resource::<PluginSystems>() {
+        warn!(
+            target: LOG_TARGET,
+            "Reloading mods without a PluginSystems resource, any systems the mods added to the schedule \
+             are not removed and will run code from the unloaded libraries. \
+             Add the PluginSystemsPlugin and have mods add their systems with \"add_plugin_system\"."
+        );
+    }
+}
+
+/// Warn if a plugin was compiled with a different build profile than the host,
+/// as types can differ in layout and behaviour between profiles.
+fn warn_if_profile_mismatch(plugin: &RustDynPlugin) {
+    if let Some(build_profile) = plugin.build_profile() {
+        if build_profile != HOST_BUILD_PROFILE {
+            warn!(
+                target: LOG_TARGET,
+                "Mod '{}' from '{}' was built in {} but the host was built in {}, \
+                 this can cause subtle ABI and layout differences. Rebuild the mod with the host's profile.",
+                plugin.name(),
+                plugin.path.display(),
+                build_profile,
+                HOST_BUILD_PROFILE
+            );
+        }
+    }
+}
+
+/// Build a dynamic plugin into an application, returning whether the build returned.
+/// With the "catch_panics" feature a panic during the build is caught and logged,
+/// otherwise it unwinds into the host. See [RustDynPlugin::build_succeeded] for the requirements.
+fn build_plugin(app: &mut App, plugin: &RustDynPlugin) -> bool {
+    #[cfg(feature = "catch_panics")]
+    {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| plugin.build(app))) {
+            Ok(()) => true,
+            Err(panic) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Mod '{}' from '{}' panicked while building and may be partially set up! {}",
+                    plugin.name(),
+                    plugin.path.display(),
+                    panic_message(&*panic)
+                );
+                false
+            }
+        }
+    }
+    #[cfg(not(feature = "catch_panics"))]
+    {
+        plugin.build(app);
+        true
+    }
+}
+
+/// The [PluginLoader] of the [ModPluginLoader] resource, or the [LibraryLoader] if there is none.
+pub(crate) fn plugin_loader(app: &App) -> Arc<dyn PluginLoader> {
+    match app.world.get_resource::<ModPluginLoader>() {
+        Some(mod_plugin_loader) => mod_plugin_loader.loader.clone(),
+        None => Arc::new(LibraryLoader),
+    }
+}
+
+/// Resolve a mods directory from an environment variable, falling back to the default directory
+/// if the variable is not set, so that the directory can be overridden without recompiling.
+/// Logs which of the two was used. If the variable is set but is not valid unicode,
+/// the default directory is used and a warning is logged.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// let mut app = App::new();
+/// app.load_mods(&mods_dir_from_env("plugins", "MY_GAME_MODS_DIR"));
+/// ```
+pub fn mods_dir_from_env(default_dir: &str, env_var: &str) -> String {
+    match std::env::var(env_var) {
+        Ok(mods_directory) => {
+            info!(
+                target: LOG_TARGET,
+                "Using mods folder '{}' from environment variable '{}'",
+                mods_directory, env_var
+            );
+            mods_directory
+        }
+        Err(std::env::VarError::NotPresent) => {
+            info!(target: LOG_TARGET, "Using default mods folder '{}'", default_dir);
+            default_dir.to_string()
+        }
+        Err(err) => {
+            warn!(
+                target: LOG_TARGET,
+                "Could not read mods folder from environment variable '{}', using default mods folder '{}' instead! {}",
+                env_var, default_dir, err
+            );
+            default_dir.to_string()
+        }
+    }
+}
+
+/// Find the paths of all files matching a glob pattern, in sorted order.
+/// An invalid pattern, or a match that cannot be read, is logged and skipped.
+/// # Example
+/// ```
+/// # use rdyn_plugins::*;
+/// for mod_path in glob_mod_paths("mods/*/plugin.dll") {
+///     println!("Found mod: {}", mod_path.display());
+/// }
+/// ```
+#[cfg(feature = "glob")]
+pub fn glob_mod_paths(pattern: &str) -> Vec<PathBuf> {
+    let matches = match glob::glob(pattern) {
+        Ok(matches) => matches,
+        Err(err) => {
+            warn!(target: LOG_TARGET, "Invalid mods pattern '{}'! {}", pattern, err);
+            return Vec::new();
+        }
+    };
+
+    let mut mod_paths: Vec<PathBuf> = matches
+        .filter_map(|mod_path| match mod_path {
+            Ok(mod_path) => Some(mod_path),
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Could not read mod path matching '{}'! {}",
+                    pattern, err
+                );
+                None
+            }
+        })
+        .filter(|mod_path| mod_path.is_file())
+        .collect();
+    mod_paths.sort();
+    mod_paths
+}
+
+/// Where a set of mods was found, so that it can be scanned again when reloading.
+#[derive(Debug, Clone)]
+pub(crate) enum ModSource {
+    Directory(String),
+    #[cfg(feature = "glob")]
+    Glob(String),
+}
+
+impl ModSource {
+    fn scan(&self) -> Result<Vec<PathBuf>, ModLoadError> {
+        match self {
+            ModSource::Directory(mods_directory) => scan_mods_directory(mods_directory),
+            #[cfg(feature = "glob")]
+            ModSource::Glob(pattern) => Ok(glob_mod_paths(pattern)),
+        }
+    }
+}
+
+/// Find the paths of all files in a mods directory,
+/// [ModLoadError::NotADirectory] if the path is a file.
+pub(crate) fn scan_mods_directory(mods_directory: &str) -> Result<Vec<PathBuf>, ModLoadError> {
+    if Path::new(mods_directory).is_file() {
+        return Err(ModLoadError::NotADirectory(PathBuf::from(mods_directory)));
+    }
+    Ok(fs::read_dir(mods_directory)?
+        .flatten()
+        .filter(|p| p.file_type().is_ok_and(|f| f.is_file()))
+        .map(|p| p.path())
+        .collect())
+}
+
+/// Log why the mods directory could not be scanned, distinguishing a missing directory from a file.
+pub(crate) fn warn_unreadable_mods_directory(mods_directory: &str, err: &ModLoadError) {
+    match err {
+        ModLoadError::Io(err) if err.kind() == std::io::ErrorKind::NotFound => warn!(
+            target: LOG_TARGET,
+            "Mods folder '{}' not found!",
+            mods_directory
+        ),
+        ModLoadError::NotADirectory(_) => warn!(
+            target: LOG_TARGET,
+            "Mods folder '{}' is a file, expected a directory!",
+            mods_directory
+        ),
+        err => warn!(
+            target: LOG_TARGET,
+            "Could not read mods folder '{}'! {}",
+            mods_directory, err
+        ),
+    }
+}
+
+/// Load the mods found at the specified paths, ordered by their load priority,
+/// into a fresh [ModLoaderData] resource with the marker `M`, then build any missing fallbacks.
+/// The sources the paths were found in are stored so the mods can be reloaded.
+fn load_found_mods_into<M: 'static>(
+    app: &mut App,
+    mut mod_paths: Vec<PathBuf>,
+    sources: Vec<ModSource>,
+) {
+    sort_by_priority(app, &mut mod_paths);
+    load_sorted_mods_with::<M>(app, &mod_paths, sources, open_mod, |_, _| {});
+}
+
+/// Load the mods at the specified paths in order, opening each with the `open` function,
+/// into a fresh [ModLoaderData] resource with the marker `M`, then build any missing fallbacks.
+pub(crate) fn load_sorted_mods_with<M: 'static>(
+    app: &mut App,
+    mod_paths: &[PathBuf],
+    sources: Vec<ModSource>,
+    open: impl FnMut(&mut App, &str) -> Result<RustDynPlugin, ModLoadError>,
+    on_each: impl FnMut(&Path, &Result<(), ModLoadError>),
+) {
+    app.insert_resource(ModLoaderData::<M> {
+        sources,
+        ..default()
+    });
+    load_mod_list_with::<M, _>(app, mod_paths, open, on_each);
+    build_missing_fallbacks::<M>(app);
+}
+
+/// Load each mod from a list of file paths in order,
+/// adding them to the [ModLoaderData] resource with the marker `M`.
+/// Paths of the host executable or of already loaded libraries are skipped.
+fn load_mod_list_into<M: 'static, P: AsRef<Path>>(app: &mut App, mod_paths: &[P]) {
+    load_mod_list_with::<M, P>(app, mod_paths, open_mod, |_, _| {});
+}
+
+/// Load each mod from a list of file paths in order, opening each with the `open` function and then building it,
+/// adding them to the [ModLoaderData] resource with the marker `M`.
+/// `on_each` is called with the outcome of every mod that is attempted.
+fn load_mod_list_with<M: 'static, P: AsRef<Path>>(
+    app: &mut App,
+    mod_paths: &[P],
+    mut open: impl FnMut(&mut App, &str) -> Result<RustDynPlugin, ModLoadError>,
+    mut on_each: impl FnMut(&Path, &Result<(), ModLoadError>),
+) {
+    let mut mod_loader_data = app
+        .world
+        .remove_resource::<ModLoaderData<M>>()
+        .unwrap_or_default();
+    let duplicate_policy = app
+        .world
+        .get_resource::<ModLoaderSettings>()
+        .map(|settings| settings.duplicate_policy)
+        .unwrap_or_default();
+
+    for mod_path in mod_paths {
+        let mod_path = mod_path.as_ref();
+        let mod_path = match mod_path.to_str() {
+            None => {
+                warn!(target: LOG_TARGET, "Failed to get path of plugin from: '{:?}'", mod_path);
+                continue;
+            }
+            Some(mod_path) if is_host_or_already_loaded(&mod_loader_data, Path::new(mod_path)) => {
+                continue
+            }
+            Some(mod_path) => mod_path,
+        };
+
+        #[cfg(feature = "trace")]
+        let _load_span = info_span!(target: LOG_TARGET, "load_mod", path = %mod_path).entered();
+        let loaded = open(app, mod_path)
+            .and_then(|plugin| check_duplicate(&mod_loader_data, plugin, duplicate_policy));
+        match build_loaded_mod(app, mod_path, loaded) {
+            Ok(plugin) => {
+                on_each(Path::new(mod_path), &Ok(()));
+                warn_if_shadowed(&mod_loader_data, &plugin);
+                mod_loader_data.loaded_plugins.push(plugin);
+            }
+            Err(err) => {
+                let outcome = Err(err);
+                on_each(Path::new(mod_path), &outcome);
+                if let Err(err) = outcome {
+                    mod_loader_data
+                        .failures
+                        .push((PathBuf::from(mod_path), err));
+                }
+            }
+        }
+    }
+
+    app.insert_resource(ModLoadReport::from_data(&mod_loader_data));
+    app.insert_resource(mod_loader_data);
+}
+
+/// Check a newly loaded plugin against the [DuplicateModPolicy],
+/// returning [ModLoadError::DuplicateName] if it must be skipped as a plugin of the same name is already loaded.
+fn check_duplicate<M>(
+    mod_loader_data: &ModLoaderData<M>,
+    plugin: RustDynPlugin,
+    duplicate_policy: DuplicateModPolicy,
+) -> Result<RustDynPlugin, ModLoadError> {
+    if duplicate_policy == DuplicateModPolicy::LoadAll {
+        return Ok(plugin);
+    }
+    match mod_loader_data
+        .iter()
+        .find(|loaded_plugin| loaded_plugin.name() == plugin.name())
+    {
+        Some(loaded_plugin) => Err(ModLoadError::DuplicateName {
+            name: plugin.name().to_string(),
+            loaded_from: loaded_plugin.path.clone(),
+        }),
+        None => Ok(plugin),
+    }
+}
+
+/// Check whether a mod path refers to the host executable itself or to the library of an already loaded plugin,
+/// logging a warning if so. Loading either would create and build the same plugin code a second time.
+/// Paths are compared after resolving symbolic links and relative components where possible.
+fn is_host_or_already_loaded<M>(mod_loader_data: &ModLoaderData<M>, mod_path: &Path) -> bool {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mod_path_canonical = canonical(mod_path);
+
+    if std::env::current_exe().is_ok_and(|exe_path| canonical(&exe_path) == mod_path_canonical) {
+        warn!(
+            target: LOG_TARGET,
+            "Skipping mod '{}' as it is the host executable, remove it from the mods folder.",
+            mod_path.display()
+        );
+        return true;
+    }
+
+    if let Some(loaded_plugin) = mod_loader_data
+        .iter()
+        .find(|plugin| canonical(&plugin.path) == mod_path_canonical)
+    {
+        warn!(
+            target: LOG_TARGET,
+            "Skipping mod '{}' as its library is already loaded as '{}' from '{}'.",
+            mod_path.display(),
+            loaded_plugin.name(),
+            loaded_plugin.path.display()
+        );
+        return true;
+    }
+
+    false
+}
+
+/// Warn if a newly loaded plugin was created by the same create function as an already loaded plugin,
+/// which means one library's exported symbols are shadowing another's, see [load_rdyn_plugin].
+fn warn_if_shadowed<M>(mod_loader_data: &ModLoaderData<M>, plugin: &RustDynPlugin) {
+    let create_symbol_address = match plugin.create_symbol_address() {
+        Some(address) => address,
+        None => return,
+    };
+
+    for loaded_plugin in mod_loader_data.iter() {
+        if loaded_plugin.create_symbol_address() == Some(create_symbol_address) {
+            warn!(
+                target: LOG_TARGET,
+                "Mod '{}' loaded from '{}' was created by the same function as mod '{}' loaded from '{}', \
+                 the libraries' symbols are likely shadowing each other due to global symbol scoping",
+                plugin.name(),
+                plugin.path.display(),
+                loaded_plugin.name(),
+                loaded_plugin.path.display()
+            );
+        }
+    }
+}
+
+/// Sort mod paths by their load priority, highest first, and then alphabetically.
+/// Priorities are read from the [MetadataCache] resource if present, so that unchanged mods are not
+/// opened to read their priority, otherwise from the application's [PluginLoader].
+fn sort_by_priority(app: &mut App, mod_paths: &mut [PathBuf]) {
+    let loader = plugin_loader(app);
+    let host_context = app.world.get_resource::<HostContext>().cloned();
+    let mut cache = app.world.get_resource_mut::<MetadataCache>();
+    mod_paths.sort_by_cached_key(|path| {
+        let priority = path.to_str().map_or(DEFAULT_RDYN_PRIORITY, |path| {
+            cache
+                .as_mut()
+                .and_then(|cache| cache.peek_with_loader(path, &*loader, host_context.as_ref()))
+                .map_or_else(|| loader.priority(path), |info| info.priority)
+        });
+        (Reverse(priority), path.clone())
+    });
+}
+
+/// Build every registered fallback that does not have a loaded mod of the same name in
+/// the [ModLoaderData] resource with the marker `M`.
+/// Fallbacks that are built are consumed, the rest are kept for later loads.
+fn build_missing_fallbacks<M: 'static>(app: &mut App) {
+    let fallbacks = match app.world.get_resource_mut::<ModFallbacks>() {
+        Some(mut fallbacks) => std::mem::take(&mut fallbacks.fallbacks),
+        None => return,
+    };
+
+    let loaded_names: Vec<String> = app
+        .world
+        .get_resource::<ModLoaderData<M>>()
+        .map(|data| {
+            data.iter()
+                .map(|plugin| plugin.name().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (unused, missing): (HashMap<_, _>, HashMap<_, _>) = fallbacks
+        .into_iter()
+        .partition(|(name, _)| loaded_names.contains(name));
+    app.insert_resource(ModFallbacks { fallbacks: unused });
+
+    for (name, fallback) in missing {
+        warn!(
+            target: LOG_TARGET,
+            "Mod '{}' was not loaded, building its fallback instead",
+            name
+        );
+        fallback.build(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A path in the temporary directory unique to the test, with nothing at it.
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rdyn_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    /// A plugin with a fixed name, standing in for the plugin of a mod's library.
+    struct NamedPlugin(String);
+
+    impl Plugin for NamedPlugin {
+        fn build(&self, _app: &mut App) {}
+
+        fn name(&self) -> &str {
+            &self.0
+        }
+    }
+
+    /// A unique temporary mods directory with an empty file at each of the relative mod paths, and a [MockLoader]
+    /// creating a [NamedPlugin] named after the file's stem for each of them, such as "base" for "core/base.dll".
+    fn mock_mods(name: &str, mod_files: &[&str]) -> (PathBuf, MockLoader) {
+        let mods_directory = temp_path(name);
+        let mut loader = MockLoader::new();
+        for mod_file in mod_files {
+            let mod_path = mods_directory.join(mod_file);
+            fs::create_dir_all(mod_path.parent().unwrap()).unwrap();
+            fs::write(&mod_path, []).unwrap();
+            let plugin_name = mod_path.file_stem().unwrap().to_str().unwrap().to_owned();
+            loader =
+                loader.with_plugin(mod_path, move || Box::new(NamedPlugin(plugin_name.clone())));
+        }
+        (mods_directory, loader)
+    }
+
+    #[test]
+    fn scan_finds_only_files() {
+        let mods_directory = temp_path("scan_files");
+        fs::create_dir_all(mods_directory.join("nested")).unwrap();
+        fs::write(mods_directory.join("mod.so"), []).unwrap();
+
+        let mod_paths = scan_mods_directory(mods_directory.to_str().unwrap()).unwrap();
+        assert_eq!(mod_paths, vec![mods_directory.join("mod.so")]);
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn scan_missing_directory_is_not_found() {
+        let mods_directory = temp_path("scan_missing");
+
+        assert!(matches!(
+            scan_mods_directory(mods_directory.to_str().unwrap()),
+            Err(ModLoadError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound
+        ));
+    }
+
+    #[test]
+    fn scan_file_is_not_a_directory() {
+        let mods_directory = temp_path("scan_file");
+        fs::write(&mods_directory, []).unwrap();
+
+        assert!(matches!(
+            scan_mods_directory(mods_directory.to_str().unwrap()),
+            Err(ModLoadError::NotADirectory(path)) if path == mods_directory
+        ));
+
+        fs::remove_file(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn load_mods_into_keeps_marked_mods_separate() {
+        struct CoreMods;
+        struct UserMods;
+
+        let (mods_directory, loader) = mock_mods(
+            "load_mods_into",
+            &["core_plugins/base.dll", "plugins/extra.dll", "listed.dll"],
+        );
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader))
+            .load_mod_list(&[mods_directory.join("listed.dll")])
+            .load_mods_into::<CoreMods>(mods_directory.join("core_plugins").to_str().unwrap())
+            .load_mods_into::<UserMods>(mods_directory.join("plugins").to_str().unwrap());
+
+        assert_eq!(
+            app.world.resource::<ModLoaderData<CoreMods>>().names(),
+            vec!["base"]
+        );
+        assert_eq!(
+            app.world.resource::<ModLoaderData<UserMods>>().names(),
+            vec!["extra"]
+        );
+        assert_eq!(
+            app.world.resource::<ModLoaderData>().names(),
+            vec!["listed"]
+        );
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn load_mods_with_callback_reports_each_attempt_in_order() {
+        let (mods_directory, loader) = mock_mods("callback", &["first.dll", "second.dll"]);
+        fs::write(mods_directory.join("broken.dll"), []).unwrap();
+        let loader = loader.with_priority(mods_directory.join("second.dll"), 10);
+
+        let mut attempts = Vec::new();
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader))
+            .load_mods_with_callback(mods_directory.to_str().unwrap(), |path, outcome| {
+                attempts.push((path.file_name().unwrap().to_owned(), outcome.is_ok()));
+            });
+
+        assert_eq!(
+            attempts,
+            [
+                ("second.dll".into(), true),
+                ("broken.dll".into(), false),
+                ("first.dll".into(), true),
+            ]
+        );
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn load_mods_all_skips_duplicates_across_directories() {
+        let (mods_directory, loader) = mock_mods(
+            "load_all",
+            &[
+                "base/core.dll",
+                "base/maps.dll",
+                "user/core.dll",
+                "user/skins.dll",
+            ],
+        );
+        let base_directory = mods_directory.join("base");
+        let user_directory = mods_directory.join("user");
+
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader))
+            .insert_resource(ModLoaderSettings {
+                duplicate_policy: DuplicateModPolicy::SkipDuplicates,
+                ..default()
+            })
+            .load_mods_all(&[&base_directory, &user_directory]);
+
+        let mod_loader_data = app.world.resource::<ModLoaderData>();
+        assert_eq!(mod_loader_data.names(), vec!["core", "maps", "skins"]);
+        assert_eq!(
+            mod_loader_data.failures()[0].0,
+            user_directory.join("core.dll")
+        );
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn reload_unchanged_mod_does_nothing() {
+        static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+        struct CountingPlugin;
+
+        impl Plugin for CountingPlugin {
+            fn build(&self, _app: &mut App) {
+                BUILDS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mod_path = temp_path("reload_unchanged");
+        fs::write(&mod_path, []).unwrap();
+        let loader = MockLoader::new().with_plugin(&mod_path, || Box::new(CountingPlugin));
+
+        let mut app = App::new();
+        app.add_plugin(PluginSystemsPlugin)
+            .insert_resource(ModPluginLoader::new(loader))
+            .load_mod_list(&[&mod_path]);
+        let name = std::any::type_name::<CountingPlugin>();
+        assert_eq!(app.reload_mod(name).unwrap(), ReloadOutcome::Unchanged);
+        assert_eq!(app.reload_mod(name).unwrap(), ReloadOutcome::Unchanged);
+        assert_eq!(BUILDS.load(Ordering::Relaxed), 1);
+        assert_eq!(app.world.resource::<ModLoaderData>().len(), 1);
+
+        fs::remove_file(mod_path).unwrap();
+    }
+
+    #[test]
+    fn load_mod_and_get_returns_inserted_resource() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct ModConfig {
+            difficulty: u32,
+        }
+
+        struct ConfigPlugin;
+
+        impl Plugin for ConfigPlugin {
+            fn build(&self, app: &mut App) {
+                app.insert_resource(ModConfig { difficulty: 3 });
+            }
+        }
+
+        let loader = MockLoader::new().with_plugin("plugins/config.dll", || Box::new(ConfigPlugin));
+
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader));
+        let (_plugin, config) = app
+            .load_mod_and_get::<ModConfig>("plugins/config.dll")
+            .unwrap();
+        assert_eq!(config, Some(ModConfig { difficulty: 3 }));
+    }
+
+    #[test]
+    fn versions_are_stored_and_reported() {
+        let (mods_directory, loader) = mock_mods("versions", &["versioned.dll", "plain.dll"]);
+        let loader = loader.with_version(mods_directory.join("versioned.dll"), "1.2.3");
+
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader))
+            .load_mod_list(&[
+                mods_directory.join("versioned.dll"),
+                mods_directory.join("plain.dll"),
+            ]);
+
+        assert_eq!(
+            app.world.resource::<ModLoaderData>().versions(),
+            vec![("versioned", Some("1.2.3")), ("plain", None)]
+        );
+        let report = app.world.resource::<ModLoadReport>();
+        assert_eq!(report.entries[0].version.as_deref(), Some("1.2.3"));
+        assert_eq!(report.entries[1].version, None);
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn metadata_cache_reads_through_plugin_loader() {
+        let (mods_directory, loader) = mock_mods("metadata_cache", &["low.dll", "high.dll"]);
+        let loader = loader.with_priority(mods_directory.join("high.dll"), 10);
+
+        let mut app = App::new();
+        app.insert_resource(ModPluginLoader::new(loader))
+            .init_resource::<MetadataCache>();
+        let high_path = mods_directory.join("high.dll");
+        assert_eq!(
+            app.peek_mod(high_path.to_str().unwrap()),
+            Some(PluginInfo {
+                name: "high".to_string(),
+                priority: 10,
+            })
+        );
+
+        app.load_mods(mods_directory.to_str().unwrap());
+        assert_eq!(
+            app.world.resource::<ModLoaderData>().names(),
+            vec!["high", "low"]
+        );
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+
+    #[test]
+    fn mods_dir_from_env_falls_back_when_unset() {
+        let env_var = format!("RDYN_TEST_MODS_DIR_{}", std::process::id());
+
+        std::env::set_var(&env_var, "custom_plugins");
+        assert_eq!(mods_dir_from_env("plugins", &env_var), "custom_plugins");
+
+        std::env::remove_var(&env_var);
+        assert_eq!(mods_dir_from_env("plugins", &env_var), "plugins");
+    }
+
+    #[cfg(feature = "glob")]
+    #[test]
+    fn glob_finds_mods_in_subdirectories() {
+        let mods_directory = temp_path("glob");
+        for mod_name in ["first", "second"] {
+            fs::create_dir_all(mods_directory.join(mod_name)).unwrap();
+            fs::write(mods_directory.join(mod_name).join("plugin.dll"), []).unwrap();
+        }
+        fs::write(mods_directory.join("second").join("readme.txt"), []).unwrap();
+
+        let pattern = mods_directory.join("*").join("plugin.dll");
+        assert_eq!(
+            glob_mod_paths(pattern.to_str().unwrap()),
+            [
+                mods_directory.join("first").join("plugin.dll"),
+                mods_directory.join("second").join("plugin.dll"),
+            ]
+        );
+
+        fs::remove_dir_all(mods_directory).unwrap();
+    }
+}
@@ -0,0 +1,383 @@
+use std::{fmt::Display, fs::File, io::Read, path::Path};
+
+use crate::error::ModLoadError;
+
+/// Number of bytes read from the start of a file to identify its platform.
+const HEADER_LEN: usize = 4096;
+
+/// The executable file format of a binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryFormat {
+    /// Executable and Linkable Format, used by Linux and most other Unix platforms.
+    Elf,
+    /// Portable Executable, used by Windows.
+    Pe,
+    /// Mach-O, used by macOS and iOS.
+    MachO,
+    Unknown,
+}
+
+/// The processor architecture a binary was built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryArch {
+    X86,
+    X86_64,
+    Arm,
+    Aarch64,
+    Unknown,
+}
+
+/// The platform a binary was built for, as identified from its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryPlatform {
+    pub format: BinaryFormat,
+    pub arch: BinaryArch,
+}
+
+impl BinaryPlatform {
+    /// The platform the host was built for.
+    pub const HOST: BinaryPlatform = BinaryPlatform {
+        format: if cfg!(windows) {
+            BinaryFormat::Pe
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            BinaryFormat::MachO
+        } else if cfg!(unix) {
+            BinaryFormat::Elf
+        } else {
+            BinaryFormat::Unknown
+        },
+        arch: if cfg!(target_arch = "x86") {
+            BinaryArch::X86
+        } else if cfg!(target_arch = "x86_64") {
+            BinaryArch::X86_64
+        } else if cfg!(target_arch = "arm") {
+            BinaryArch::Arm
+        } else if cfg!(target_arch = "aarch64") {
+            BinaryArch::Aarch64
+        } else {
+            BinaryArch::Unknown
+        },
+    };
+
+    /// Identify the platform of a binary from the bytes at the start of its file.
+    pub fn from_header(header: &[u8]) -> BinaryPlatform {
+        let u16_at = |offset: usize, little_endian: bool| {
+            let bytes = [*header.get(offset)?, *header.get(offset + 1)?];
+            Some(match little_endian {
+                true => u16::from_le_bytes(bytes),
+                false => u16::from_be_bytes(bytes),
+            })
+        };
+        let u32_le_at = |offset: usize| {
+            let bytes = header.get(offset..offset + 4)?;
+            Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+
+        match header {
+            [0x7F, b'E', b'L', b'F', ..] => {
+                // EI_DATA of 1 is little endian, e_machine follows the 16 byte identifier and e_type.
+                let little_endian = header.get(5) == Some(&1);
+                let arch = match u16_at(18, little_endian) {
+                    Some(0x03) => BinaryArch::X86,
+                    Some(0x3E) => BinaryArch::X86_64,
+                    Some(0x28) => BinaryArch::Arm,
+                    Some(0xB7) => BinaryArch::Aarch64,
+                    _ => BinaryArch::Unknown,
+                };
+                BinaryPlatform {
+                    format: BinaryFormat::Elf,
+                    arch,
+                }
+            }
+            [b'M', b'Z', ..] => {
+                // e_lfanew at 0x3C holds the offset of the "PE\0\0" signature, followed by the machine type.
+                let arch = match u32_le_at(0x3C).map(|offset| offset as usize) {
+                    Some(pe_offset) if header.get(pe_offset..pe_offset + 4) == Some(b"PE\0\0") => {
+                        match u16_at(pe_offset + 4, true) {
+                            Some(0x014C) => BinaryArch::X86,
+                            Some(0x8664) => BinaryArch::X86_64,
+                            Some(0x01C0) | Some(0x01C4) => BinaryArch::Arm,
+                            Some(0xAA64) => BinaryArch::Aarch64,
+                            _ => BinaryArch::Unknown,
+                        }
+                    }
+                    _ => BinaryArch::Unknown,
+                };
+                BinaryPlatform {
+                    format: BinaryFormat::Pe,
+                    arch,
+                }
+            }
+            [0xCE, 0xFA, 0xED, 0xFE, ..] | [0xCF, 0xFA, 0xED, 0xFE, ..] => {
+                let arch = match u32_le_at(4) {
+                    Some(0x0000_0007) => BinaryArch::X86,
+                    Some(0x0100_0007) => BinaryArch::X86_64,
+                    Some(0x0000_000C) => BinaryArch::Arm,
+                    Some(0x0100_000C) => BinaryArch::Aarch64,
+                    _ => BinaryArch::Unknown,
+                };
+                BinaryPlatform {
+                    format: BinaryFormat::MachO,
+                    arch,
+                }
+            }
+            // Universal binaries contain multiple architectures, leave the choice to the loader.
+            [0xCA, 0xFE, 0xBA, 0xBE, ..] => BinaryPlatform {
+                format: BinaryFormat::MachO,
+                arch: BinaryArch::Unknown,
+            },
+            _ => BinaryPlatform {
+                format: BinaryFormat::Unknown,
+                arch: BinaryArch::Unknown,
+            },
+        }
+    }
+
+    /// Identify the platform of the binary at the specified path from its header.
+    pub fn read_from(path: impl AsRef<Path>) -> std::io::Result<BinaryPlatform> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        File::open(path)?
+            .take(HEADER_LEN as u64)
+            .read_to_end(&mut header)?;
+        Ok(Self::from_header(&header))
+    }
+
+    /// Whether a binary of this platform can be loaded by a host of the `host` platform.
+    /// An unknown architecture on either side is assumed to be compatible.
+    pub fn is_loadable_by(&self, host: &BinaryPlatform) -> bool {
+        let arch_matches = self.arch == host.arch
+            || self.arch == BinaryArch::Unknown
+            || host.arch == BinaryArch::Unknown;
+        self.format == host.format && arch_matches
+    }
+}
+
+impl Display for BinaryFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BinaryFormat::Elf => "ELF",
+            BinaryFormat::Pe => "PE",
+            BinaryFormat::MachO => "Mach-O",
+            BinaryFormat::Unknown => "unknown format",
+        })
+    }
+}
+
+impl Display for BinaryArch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BinaryArch::X86 => "x86",
+            BinaryArch::X86_64 => "x86_64",
+            BinaryArch::Arm => "arm",
+            BinaryArch::Aarch64 => "aarch64",
+            BinaryArch::Unknown => "unknown architecture",
+        })
+    }
+}
+
+impl Display for BinaryPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.format, self.arch)
+    }
+}
+
+/// Check that the file at the specified path is a dynamic library built for the platform of the host,
+/// by reading its header.
+/// Always succeeds on hosts whose own format is unknown.
+pub fn check_platform(path: impl AsRef<Path>) -> Result<(), ModLoadError> {
+    if BinaryPlatform::HOST.format == BinaryFormat::Unknown {
+        return Ok(());
+    }
+
+    let found = BinaryPlatform::read_from(path)?;
+    match found.is_loadable_by(&BinaryPlatform::HOST) {
+        true => Ok(()),
+        false => Err(ModLoadError::WrongPlatform {
+            expected: BinaryPlatform::HOST,
+            found,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(format: BinaryFormat, arch: BinaryArch) -> BinaryPlatform {
+        BinaryPlatform { format, arch }
+    }
+
+    /// An ELF header with the specified EI_DATA and e_machine, encoded in that endianness.
+    fn elf_header(ei_data: u8, machine: u16) -> Vec<u8> {
+        let mut header = vec![0; 64];
+        header[..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        header[4] = 2;
+        header[5] = ei_data;
+        let machine = match ei_data {
+            1 => machine.to_le_bytes(),
+            _ => machine.to_be_bytes(),
+        };
+        header[18..20].copy_from_slice(&machine);
+        header
+    }
+
+    /// A PE header with the "PE\0\0" signature at `pe_offset`, followed by the machine type.
+    fn pe_header(pe_offset: u32, machine: u16) -> Vec<u8> {
+        let mut header = vec![0; pe_offset as usize + 24];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3C..0x40].copy_from_slice(&pe_offset.to_le_bytes());
+        let pe_offset = pe_offset as usize;
+        header[pe_offset..pe_offset + 4].copy_from_slice(b"PE\0\0");
+        header[pe_offset + 4..pe_offset + 6].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    /// A thin Mach-O header with the specified magic and cputype.
+    fn macho_header(magic: u32, cpu_type: u32) -> Vec<u8> {
+        let mut header = magic.to_le_bytes().to_vec();
+        header.extend_from_slice(&cpu_type.to_le_bytes());
+        header.resize(32, 0);
+        header
+    }
+
+    #[test]
+    fn elf_little_endian() {
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(1, 0x3E)),
+            platform(BinaryFormat::Elf, BinaryArch::X86_64)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(1, 0xB7)),
+            platform(BinaryFormat::Elf, BinaryArch::Aarch64)
+        );
+    }
+
+    #[test]
+    fn elf_big_endian() {
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(2, 0x28)),
+            platform(BinaryFormat::Elf, BinaryArch::Arm)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(2, 0x03)),
+            platform(BinaryFormat::Elf, BinaryArch::X86)
+        );
+    }
+
+    #[test]
+    fn pe_machine_after_e_lfanew() {
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0x80, 0x8664)),
+            platform(BinaryFormat::Pe, BinaryArch::X86_64)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0x100, 0x014C)),
+            platform(BinaryFormat::Pe, BinaryArch::X86)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0xF8, 0xAA64)),
+            platform(BinaryFormat::Pe, BinaryArch::Aarch64)
+        );
+    }
+
+    #[test]
+    fn pe_without_signature() {
+        let mut header = pe_header(0x80, 0x8664);
+        header[0x80] = b'X';
+        assert_eq!(
+            BinaryPlatform::from_header(&header),
+            platform(BinaryFormat::Pe, BinaryArch::Unknown)
+        );
+    }
+
+    #[test]
+    fn macho_thin() {
+        assert_eq!(
+            BinaryPlatform::from_header(&macho_header(0xFEED_FACF, 0x0100_0007)),
+            platform(BinaryFormat::MachO, BinaryArch::X86_64)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&macho_header(0xFEED_FACF, 0x0100_000C)),
+            platform(BinaryFormat::MachO, BinaryArch::Aarch64)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&macho_header(0xFEED_FACE, 0x0000_0007)),
+            platform(BinaryFormat::MachO, BinaryArch::X86)
+        );
+    }
+
+    #[test]
+    fn macho_fat() {
+        let mut header = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2];
+        header.resize(64, 0);
+        assert_eq!(
+            BinaryPlatform::from_header(&header),
+            platform(BinaryFormat::MachO, BinaryArch::Unknown)
+        );
+    }
+
+    #[test]
+    fn truncated_headers() {
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(1, 0x3E)[..19]),
+            platform(BinaryFormat::Elf, BinaryArch::Unknown)
+        );
+        // e_lfanew points past the end of the header.
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0x80, 0x8664)[..0x82]),
+            platform(BinaryFormat::Pe, BinaryArch::Unknown)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0x80, 0x8664)[..0x3E]),
+            platform(BinaryFormat::Pe, BinaryArch::Unknown)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&macho_header(0xFEED_FACF, 0x0100_0007)[..6]),
+            platform(BinaryFormat::MachO, BinaryArch::Unknown)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&[0x7F, b'E']),
+            platform(BinaryFormat::Unknown, BinaryArch::Unknown)
+        );
+    }
+
+    #[test]
+    fn unknown_inputs() {
+        let unknown = platform(BinaryFormat::Unknown, BinaryArch::Unknown);
+        assert_eq!(BinaryPlatform::from_header(&[]), unknown);
+        assert_eq!(
+            BinaryPlatform::from_header(b"#!/bin/sh\necho mod\n"),
+            unknown
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&elf_header(1, 0x1234)),
+            platform(BinaryFormat::Elf, BinaryArch::Unknown)
+        );
+        assert_eq!(
+            BinaryPlatform::from_header(&pe_header(0x80, 0x1234)),
+            platform(BinaryFormat::Pe, BinaryArch::Unknown)
+        );
+    }
+
+    #[test]
+    fn loadable_by_matching_platform() {
+        let host = platform(BinaryFormat::Elf, BinaryArch::X86_64);
+        assert!(BinaryPlatform::from_header(&elf_header(1, 0x3E)).is_loadable_by(&host));
+        assert!(!BinaryPlatform::from_header(&elf_header(1, 0xB7)).is_loadable_by(&host));
+        assert!(!BinaryPlatform::from_header(&pe_header(0x80, 0x8664)).is_loadable_by(&host));
+        assert!(!BinaryPlatform::from_header(&[]).is_loadable_by(&host));
+    }
+
+    #[test]
+    fn loadable_by_unknown_arch() {
+        let host = platform(BinaryFormat::MachO, BinaryArch::Aarch64);
+        let fat = [0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2];
+        assert!(BinaryPlatform::from_header(&fat).is_loadable_by(&host));
+        let unknown_host = platform(BinaryFormat::MachO, BinaryArch::Unknown);
+        assert!(
+            BinaryPlatform::from_header(&macho_header(0xFEED_FACF, 0x0100_0007))
+                .is_loadable_by(&unknown_host)
+        );
+        assert!(!BinaryPlatform::from_header(&elf_header(1, 0x3E)).is_loadable_by(&unknown_host));
+    }
+}
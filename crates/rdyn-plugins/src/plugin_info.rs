@@ -0,0 +1,125 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{dyn_api::*, host_context::*, plugin_loader::*};
+
+/// Metadata about a rust dynamic plugin, read without building it.
+///
+/// With the "serde" feature enabled this can be serialized, so that the results of a directory scan
+/// can be persisted and reloaded without reopening every library.
+/// # Example
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// # use rdyn_plugins::*;
+/// let info = PluginInfo {
+///     name: "example::ExamplePlugin".to_string(),
+///     priority: 10,
+/// };
+/// let json = serde_json::to_string(&info).unwrap();
+/// assert_eq!(serde_json::from_str::<PluginInfo>(&json).unwrap(), info);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PluginInfo {
+    /// The name of the plugin as reported by [Plugin::name](bevy::prelude::Plugin::name).
+    pub name: String,
+    /// The load priority of the plugin, see [PRIORITY_RDYN_SYM_NAME].
+    pub priority: i32,
+}
+
+/// Read the metadata of a rust dynamic plugin from the specified path without building it.
+/// The library is opened, the plugin is created to query its metadata, and then both are released.
+/// # Unsafety
+/// Undefined behaviour expected if the symbol loaded from [CREATE_RDYN_SYM_NAME]
+/// does not match the function signature [CreateRDynPlugin]
+pub fn peek_plugin_info(path: &str) -> Option<PluginInfo> {
+    peek_plugin_info_with_context(path, None)
+}
+
+/// Read the metadata of a rust dynamic plugin from the specified path without building it as by [peek_plugin_info],
+/// passing the [HostContext] to the plugin before it is created if one is provided.
+/// # Unsafety
+/// Undefined behaviour expected if the symbols loaded from [CREATE_RDYN_SYM_NAME]
+/// and [INSTALL_HOST_CONTEXT_SYM_NAME] do not match the function signatures
+/// [CreateRDynPlugin] and [InstallRDynHostContext]
+pub fn peek_plugin_info_with_context(
+    path: &str,
+    host_context: Option<&HostContext>,
+) -> Option<PluginInfo> {
+    let RustDynPlugin {
+        library, plugin, ..
+    } = load_rdyn_plugin_with_context(path, host_context).ok()?;
+    let info = PluginInfo {
+        name: plugin.name().to_string(),
+        priority: read_rdyn_priority(&library).unwrap_or(DEFAULT_RDYN_PRIORITY),
+    };
+
+    // The plugin's code lives in the library, so it must be dropped first.
+    drop(plugin);
+    drop(library);
+    Some(info)
+}
+
+/// Caches [PluginInfo] read from plugins so that listing the same plugins again
+/// does not reopen every library.
+/// Entries are keyed by canonical path and invalidated when the file's modified time changes.
+///
+/// Insert this as a resource to have the "peek_mod" extension method, and the load priority lookup
+/// when loading mods from a directory, make use of it. Missing entries are then read with the app's
+/// [PluginLoader], passing the app's [HostContext]. The cache lives for as long as the resource does.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    entries: HashMap<PathBuf, (SystemTime, PluginInfo)>,
+}
+
+impl MetadataCache {
+    /// Get the metadata of the plugin at the specified path,
+    /// only reading it from the library if it is not cached or the file has changed since.
+    pub fn peek(&mut self, path: &str) -> Option<PluginInfo> {
+        self.peek_with_loader(path, &LibraryLoader, None)
+    }
+
+    /// Get the metadata of the plugin at the specified path as by [MetadataCache::peek], reading it with
+    /// the specified [PluginLoader] and passing the [HostContext] to the plugin if one is provided.
+    pub fn peek_with_loader(
+        &mut self,
+        path: &str,
+        loader: &dyn PluginLoader,
+        host_context: Option<&HostContext>,
+    ) -> Option<PluginInfo> {
+        let canonical_path = fs::canonicalize(path).ok()?;
+        let modified = fs::metadata(&canonical_path)
+            .and_then(|m| m.modified())
+            .ok()?;
+
+        if let Some((cached_modified, info)) = self.entries.get(&canonical_path) {
+            if *cached_modified == modified {
+                return Some(info.clone());
+            }
+        }
+
+        let info = loader.info(path, host_context)?;
+        self.entries
+            .insert(canonical_path, (modified, info.clone()));
+        Some(info)
+    }
+
+    /// Remove the cached metadata for the plugin at the specified path, if any.
+    pub fn invalidate(&mut self, path: impl AsRef<Path>) {
+        if let Ok(canonical_path) = fs::canonicalize(path) {
+            self.entries.remove(&canonical_path);
+        }
+    }
+
+    /// Remove all cached metadata.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
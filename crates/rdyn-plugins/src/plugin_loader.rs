@@ -0,0 +1,179 @@
+use std::sync::Arc;
+#[cfg(any(test, feature = "test-utils"))]
+use std::{collections::HashMap, io, path::PathBuf};
+
+#[cfg(any(test, feature = "test-utils"))]
+use bevy::prelude::Plugin;
+
+use crate::{dyn_api::*, error::*, host_context::*, plugin_info::*};
+
+/// Abstraction over the step that turns a mod's file path into a plugin,
+/// used by [ModLoaderExt](crate::ModLoaderExt) for every mod it loads.
+///
+/// The default [LibraryLoader] opens the file as a dynamic library.
+/// Insert a [ModPluginLoader] resource to replace it, such as with a [MockLoader]
+/// to test a host's mod management without building real libraries.
+pub trait PluginLoader: Send + Sync {
+    /// Load the plugin at the specified path,
+    /// passing the [HostContext] to the plugin before it is created if one is provided.
+    fn load(
+        &self,
+        path: &str,
+        host_context: Option<&HostContext>,
+    ) -> Result<RustDynPlugin, ModLoadError>;
+
+    /// The load priority of the plugin at the specified path, see [PRIORITY_RDYN_SYM_NAME].
+    fn priority(&self, path: &str) -> i32 {
+        peek_rdyn_priority(path)
+    }
+
+    /// Read the metadata of the plugin at the specified path without building it,
+    /// passing the [HostContext] to the plugin before it is created if one is provided.
+    fn info(&self, path: &str, host_context: Option<&HostContext>) -> Option<PluginInfo> {
+        let plugin = self.load(path, host_context).ok()?;
+        Some(PluginInfo {
+            name: plugin.name().to_string(),
+            priority: self.priority(path),
+        })
+    }
+}
+
+/// Loads plugins from dynamic libraries, see [load_rdyn_plugin_with_context].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LibraryLoader;
+
+impl PluginLoader for LibraryLoader {
+    fn load(
+        &self,
+        path: &str,
+        host_context: Option<&HostContext>,
+    ) -> Result<RustDynPlugin, ModLoadError> {
+        load_rdyn_plugin_with_context(path, host_context)
+    }
+
+    fn info(&self, path: &str, host_context: Option<&HostContext>) -> Option<PluginInfo> {
+        peek_plugin_info_with_context(path, host_context)
+    }
+}
+
+/// The [PluginLoader] used to load mods into an application, [LibraryLoader] if not present.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// let mut app = App::new();
+/// app.insert_resource(ModPluginLoader::new(LibraryLoader))
+///     .load_mods("plugins");
+/// ```
+#[derive(Clone)]
+pub struct ModPluginLoader {
+    pub loader: Arc<dyn PluginLoader>,
+}
+
+impl ModPluginLoader {
+    pub fn new(loader: impl PluginLoader + 'static) -> Self {
+        Self {
+            loader: Arc::new(loader),
+        }
+    }
+}
+
+impl Default for ModPluginLoader {
+    fn default() -> Self {
+        Self::new(LibraryLoader)
+    }
+}
+
+/// Factory creating the plugin for a path registered with a [MockLoader].
+#[cfg(any(test, feature = "test-utils"))]
+pub type MockPluginFactory = Box<dyn Fn() -> Box<dyn Plugin> + Send + Sync>;
+
+/// A [PluginLoader] that creates plugins in process from factories registered per path,
+/// for testing a host's mod management without compiling and loading real libraries.
+///
+/// Paths without a registered factory fail to load. Mods are still found by scanning the real
+/// mods directory, so create a file for each registered path, an empty file is enough.
+/// Plugins are wrapped with [RustDynPlugin::from_static].
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// struct CorePlugin;
+///
+/// impl Plugin for CorePlugin {
+///     fn build(&self, app: &mut App) {}
+/// }
+///
+/// let loader = MockLoader::new()
+///     .with_plugin("plugins/core.dll", || Box::new(CorePlugin))
+///     .with_priority("plugins/core.dll", 10);
+///
+/// let mut app = App::new();
+/// app.insert_resource(ModPluginLoader::new(loader))
+///     .load_mod_list(&["plugins/core.dll", "plugins/missing.dll"]);
+/// assert_eq!(app.world.resource::<ModLoaderData>().len(), 1);
+/// ```
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Default)]
+pub struct MockLoader {
+    factories: HashMap<PathBuf, MockPluginFactory>,
+    priorities: HashMap<PathBuf, i32>,
+    versions: HashMap<PathBuf, String>,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl MockLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the factory creating the plugin loaded from the specified path.
+    pub fn with_plugin(
+        mut self,
+        path: impl Into<PathBuf>,
+        factory: impl Fn() -> Box<dyn Plugin> + Send + Sync + 'static,
+    ) -> Self {
+        self.factories.insert(path.into(), Box::new(factory));
+        self
+    }
+
+    /// Set the load priority reported for the specified path, [DEFAULT_RDYN_PRIORITY] if not set.
+    pub fn with_priority(mut self, path: impl Into<PathBuf>, priority: i32) -> Self {
+        self.priorities.insert(path.into(), priority);
+        self
+    }
+
+    /// Set the version reported by the plugin loaded from the specified path, see [RustDynPlugin::version].
+    pub fn with_version(mut self, path: impl Into<PathBuf>, version: impl Into<String>) -> Self {
+        self.versions.insert(path.into(), version.into());
+        self
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl PluginLoader for MockLoader {
+    fn load(
+        &self,
+        path: &str,
+        _host_context: Option<&HostContext>,
+    ) -> Result<RustDynPlugin, ModLoadError> {
+        let factory = self.factories.get(&PathBuf::from(path)).ok_or_else(|| {
+            ModLoadError::LibraryOpenFailed(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no mock plugin registered for path",
+            ))
+        })?;
+        let plugin = RustDynPlugin::from_static(factory(), path)?;
+        Ok(match self.versions.get(&PathBuf::from(path)) {
+            Some(version) => plugin.with_version(version.clone()),
+            None => plugin,
+        })
+    }
+
+    fn priority(&self, path: &str) -> i32 {
+        self.priorities
+            .get(&PathBuf::from(path))
+            .copied()
+            .unwrap_or(DEFAULT_RDYN_PRIORITY)
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::{ecs::system::BoxedSystem, prelude::*};
+
+/// A system contributed by a dynamic plugin along with the name of the plugin it belongs to.
+struct PluginSystem {
+    plugin_name: String,
+    system: BoxedSystem,
+    initialized: bool,
+}
+
+/// Stores the systems dynamic plugins contribute to a host-owned dispatcher,
+/// as an alternative to adding them to the app's schedule.
+///
+/// Bevy does not allow systems to be removed from a schedule, so systems a plugin adds
+/// with "add_system" outlive the plugin and cannot be replaced when it is reloaded.
+/// Systems stored here are run by [run_plugin_systems] and can be cleared per plugin,
+/// so that a plugin can be unloaded and rebuilt without leaving stale systems behind.
+///
+/// Add the [PluginSystemsPlugin] to the host to set up the resource and its runner,
+/// plugins then add their systems via the [PluginSystemsExt] extension method.
+#[derive(Default)]
+pub struct PluginSystems {
+    systems: Vec<PluginSystem>,
+}
+
+impl PluginSystems {
+    /// Add a system belonging to the plugin with the specified name.
+    pub fn add_system<Params>(
+        &mut self,
+        plugin_name: &str,
+        system: impl IntoSystem<(), (), Params>,
+    ) -> &mut Self {
+        self.systems.push(PluginSystem {
+            plugin_name: plugin_name.to_string(),
+            system: Box::new(IntoSystem::into_system(system)),
+            initialized: false,
+        });
+        self
+    }
+
+    /// Remove all systems belonging to the plugin with the specified name.
+    /// This must be done before the plugin's library is unloaded, as the systems' code lives in it.
+    pub fn clear_plugin(&mut self, plugin_name: &str) {
+        self.systems
+            .retain(|system| system.plugin_name != plugin_name);
+    }
+
+    /// Remove all systems.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.systems.clear();
+    }
+
+    /// The number of systems belonging to the plugin with the specified name.
+    pub fn plugin_system_count(&self, plugin_name: &str) -> usize {
+        self.systems
+            .iter()
+            .filter(|system| system.plugin_name == plugin_name)
+            .count()
+    }
+}
+
+/// Exclusive system that runs every system stored in the [PluginSystems] resource, in the order they were added.
+pub fn run_plugin_systems(world: &mut World) {
+    if !world.contains_resource::<PluginSystems>() {
+        return;
+    }
+
+    world.resource_scope(|world, mut plugin_systems: Mut<PluginSystems>| {
+        for plugin_system in plugin_systems.systems.iter_mut() {
+            if !plugin_system.initialized {
+                plugin_system.system.initialize(world);
+                plugin_system.initialized = true;
+            }
+            plugin_system.system.run((), world);
+            plugin_system.system.apply_buffers(world);
+        }
+    });
+}
+
+/// Sets up the [PluginSystems] resource and runs its systems every update.
+pub struct PluginSystemsPlugin;
+
+impl Plugin for PluginSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PluginSystems>()
+            .add_system(run_plugin_systems.exclusive_system());
+    }
+}
+
+/// API extension for bevy to allow plugins to add systems to the host's [PluginSystems].
+pub trait PluginSystemsExt {
+    /// Add a system belonging to the specified plugin to the host's [PluginSystems],
+    /// so that it is removed when the plugin is unloaded.
+    /// # Example
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use rdyn_plugins::*;
+    /// pub struct ExamplePlugin;
+    ///
+    /// impl Plugin for ExamplePlugin {
+    ///     fn build(&self, app: &mut App) {
+    ///         app.add_plugin_system(self, Self::on_update);
+    ///     }
+    /// }
+    ///
+    /// impl ExamplePlugin {
+    ///     pub fn on_update() {}
+    /// }
+    /// ```
+    fn add_plugin_system<Params>(
+        &mut self,
+        plugin: &dyn Plugin,
+        system: impl IntoSystem<(), (), Params>,
+    ) -> &mut Self;
+}
+
+impl PluginSystemsExt for App {
+    fn add_plugin_system<Params>(
+        &mut self,
+        plugin: &dyn Plugin,
+        system: impl IntoSystem<(), (), Params>,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(PluginSystems::default)
+            .add_system(plugin.name(), system);
+        self
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use libloading::Library;
+
+use crate::error::*;
+
+static GLOBAL_PLUGIN_REGISTRY: OnceLock<PluginRegistry> = OnceLock::new();
+
+/// A process-global cache of plugin libraries, keyed by canonical path, for programs that create and drop
+/// multiple apps loading the same mods, such as editors.
+///
+/// Set [ModLoaderSettings::use_registry](crate::ModLoaderSettings::use_registry) to have mods loaded by an app
+/// retained here. The first app to load a mod opens its library as usual and the registry keeps its own handle
+/// to it. Every later app loading the same mod is given the library that is already mapped by the platform's loader,
+/// so its code is not mapped, relocated and initialised again.
+/// # Lifetime and memory
+/// Retained libraries are never unloaded, they stay mapped until the process exits.
+/// This is what allows them to outlive every app, but it means the memory of every mod ever loaded is kept,
+/// and a mod that changes on disk is not picked up by later apps. Reload such mods with "reload_mod",
+/// which loads a copy of the changed file.
+///
+/// Global state in a retained library, such as statics, is also kept between apps rather than being reset.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// for _ in 0..2 {
+///     App::new()
+///         .insert_resource(ModLoaderSettings {
+///             use_registry: true,
+///             ..default()
+///         })
+///         .load_mods("plugins");
+/// }
+/// ```
+pub struct PluginRegistry {
+    libraries: Mutex<HashMap<PathBuf, Library>>,
+}
+
+impl PluginRegistry {
+    /// The registry shared by the whole process.
+    pub fn global() -> &'static PluginRegistry {
+        GLOBAL_PLUGIN_REGISTRY.get_or_init(|| PluginRegistry {
+            libraries: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Open the library at the specified path and keep it loaded for the rest of the process,
+    /// does nothing if it has already been retained.
+    pub fn retain(&self, path: &str) -> Result<(), ModLoadError> {
+        let canonical_path = std::fs::canonicalize(path)?;
+        if let Entry::Vacant(entry) = self.lock().entry(canonical_path) {
+            let library = Library::new(entry.key()).map_err(ModLoadError::LibraryOpenFailed)?;
+            entry.insert(library);
+        }
+        Ok(())
+    }
+
+    /// Whether the library at the specified path has been retained.
+    pub fn contains(&self, path: &str) -> bool {
+        std::fs::canonicalize(path)
+            .is_ok_and(|canonical_path| self.lock().contains_key(&canonical_path))
+    }
+
+    /// The canonical paths of all retained libraries.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// The number of retained libraries.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no libraries have been retained.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, Library>> {
+        // The map is never left inconsistent, so a panic while it was locked does not matter.
+        self.libraries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
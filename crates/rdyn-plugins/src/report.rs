@@ -0,0 +1,124 @@
+use std::{fmt::Debug, marker::PhantomData, path::PathBuf};
+
+use crate::{error::*, mod_loader::*};
+
+/// The outcome of loading a single mod file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModStatus {
+    /// The mod was loaded and built.
+    Loaded,
+    /// The mod's library was loaded but its build did not succeed, see [RustDynPlugin::build_succeeded](crate::RustDynPlugin::build_succeeded).
+    BuildFailed,
+    /// The mod was skipped by the host's settings, such as its [CapabilityPolicy](crate::CapabilityPolicy)
+    /// or [host flags](crate::ModLoaderSettings::host_flags).
+    Disabled,
+    /// The mod could not be loaded.
+    Failed,
+}
+
+/// A summary of a single mod file found while loading mods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModEntry {
+    /// The path of the mod's file.
+    pub path: PathBuf,
+    /// The name of the mod's plugin, if it was loaded.
+    pub name: Option<String>,
+    /// The version of the mod's plugin, if it was loaded and reports one, see [RustDynPlugin::version](crate::RustDynPlugin::version).
+    pub version: Option<String>,
+    /// Whether the mod was loaded, failed to build, was skipped by the host's settings or failed to load.
+    pub status: ModStatus,
+    /// Why the mod is disabled or failed to load.
+    pub error: Option<String>,
+}
+
+impl ModEntry {
+    /// Whether the mod was not skipped by the host's settings.
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        self.status != ModStatus::Disabled
+    }
+}
+
+/// A read-only summary of the mods stored in the [ModLoaderData] resource with the marker `M`,
+/// kept up to date as mods are loaded and unloaded.
+/// Intended for rendering in a user interface, without access to the loaded libraries or plugins.
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use rdyn_plugins::*;
+/// fn mods_menu(report: Res<ModLoadReport>) {
+///     for entry in report.entries.iter() {
+///         println!("{}: {:?}", entry.path.display(), entry.status);
+///     }
+/// }
+/// ```
+pub struct ModLoadReport<M = ()> {
+    /// Loaded mods in load order, followed by the mods that were not loaded.
+    pub entries: Vec<ModEntry>,
+    marker: PhantomData<fn() -> M>,
+}
+
+impl<M> Default for ModLoadReport<M> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> Debug for ModLoadReport<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModLoadReport")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl<M> Clone for ModLoadReport<M> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M> ModLoadReport<M> {
+    /// Summarise the mods stored in a [ModLoaderData].
+    pub fn from_data(mod_loader_data: &ModLoaderData<M>) -> Self {
+        let loaded = mod_loader_data
+            .loaded_plugins
+            .iter()
+            .map(|plugin| ModEntry {
+                path: plugin.path.clone(),
+                name: Some(plugin.name().to_string()),
+                version: plugin.version().map(str::to_string),
+                status: match plugin.build_succeeded {
+                    true => ModStatus::Loaded,
+                    false => ModStatus::BuildFailed,
+                },
+                error: None,
+            });
+        let failed = mod_loader_data
+            .failures()
+            .iter()
+            .map(|(path, err)| ModEntry {
+                path: path.clone(),
+                name: None,
+                version: None,
+                status: match err {
+                    ModLoadError::CapabilityDenied { .. }
+                    | ModLoadError::MissingFlags { .. }
+                    | ModLoadError::DuplicateName { .. } => ModStatus::Disabled,
+                    _ => ModStatus::Failed,
+                },
+                error: Some(err.to_string()),
+            });
+
+        Self {
+            entries: loaded.chain(failed).collect(),
+            marker: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+/// Adds directories to the search path the platform uses to resolve the dependencies of a dynamic library,
+/// restoring the previous search path when restored.
+///
+/// On Windows the directories are prepended to the `PATH` environment variable, which `LoadLibrary`
+/// searches for dependent libraries. On other platforms the search path is fixed when the process starts
+/// (`LD_LIBRARY_PATH` and `DYLD_LIBRARY_PATH` are only read by the dynamic linker at startup),
+/// so nothing is changed and dependencies must be found through the library's `RPATH`/`RUNPATH`,
+/// such as `$ORIGIN/deps` on Linux or `@loader_path/deps` on macOS.
+pub(crate) struct SearchPathGuard {
+    #[cfg(windows)]
+    previous_path: Option<std::ffi::OsString>,
+}
+
+impl SearchPathGuard {
+    #[cfg(windows)]
+    pub(crate) fn install(search_dirs: &[PathBuf]) -> Self {
+        let previous_path = std::env::var_os("PATH");
+        let previous_dirs = previous_path
+            .iter()
+            .flat_map(std::env::split_paths)
+            .collect::<Vec<_>>();
+        match std::env::join_paths(search_dirs.iter().cloned().chain(previous_dirs)) {
+            Ok(path) => std::env::set_var("PATH", path),
+            Err(err) => bevy::log::warn!(
+                target: crate::LOG_TARGET,
+                "Could not add mod search directories to PATH! {}",
+                err
+            ),
+        }
+        Self { previous_path }
+    }
+
+    #[cfg(not(windows))]
+    pub(crate) fn install(_search_dirs: &[PathBuf]) -> Self {
+        Self {}
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn restore(self) {
+        match self.previous_path {
+            Some(path) => std::env::set_var("PATH", path),
+            None => std::env::remove_var("PATH"),
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub(crate) fn restore(self) {}
+}
@@ -0,0 +1,56 @@
+use libloading::{Library, Symbol};
+
+use crate::{dyn_api::*, error::*};
+
+/// Name of the optional symbol exported by a plugin to report its own version, such as `"1.2.3"`.
+/// Exported by the RDynPlugin derive with the `#[rdyn_plugin(versioned)]` option, from the plugin's
+/// [RDynVersioned] implementation. Read when the plugin is loaded, see [RustDynPlugin::version].
+/// # Example
+/// ```
+/// #[no_mangle]
+/// pub extern "Rust" fn _rdyn_version() -> &'static str {
+///     env!("CARGO_PKG_VERSION")
+/// }
+/// ```
+pub const VERSION_RDYN_SYM_NAME: &[u8] = b"_rdyn_version";
+/// Type that represents the function signature of the version symbol.
+/// Returns a static string so that no allocation crosses the library boundary, the host copies it.
+pub type RDynVersion = fn() -> &'static str;
+
+/// Companion trait to [Plugin](bevy::prelude::Plugin) for plugins that report their own version.
+/// A `Box<dyn Plugin>` cannot be downcast to this trait, so the RDynPlugin derive exports it
+/// through the [version symbol](VERSION_RDYN_SYM_NAME) when given the `#[rdyn_plugin(versioned)]` option.
+/// # Example
+/// ```ignore
+/// #[derive(RDynPlugin)]
+/// #[rdyn_plugin(versioned)]
+/// pub struct ExamplePlugin;
+///
+/// impl RDynVersioned for ExamplePlugin {
+///     fn version(&self) -> &'static str {
+///         env!("CARGO_PKG_VERSION")
+///     }
+/// }
+/// ```
+pub trait RDynVersioned {
+    /// The version of the plugin.
+    fn version(&self) -> &'static str {
+        "unknown"
+    }
+}
+
+/// Read the version of a rust dynamic plugin from the specified path without creating the plugin,
+/// `None` if it does not export the symbol.
+/// # Unsafety
+/// Undefined behaviour expected if the symbol loaded from [VERSION_RDYN_SYM_NAME]
+/// does not match the function signature [RDynVersion]
+pub fn peek_rdyn_version(path: &str) -> Result<Option<String>, ModLoadError> {
+    let loaded_library = load_rdyn_library(path)?;
+    Ok(read_rdyn_version(&loaded_library.library))
+}
+
+/// Read the version of an already loaded library, `None` if it does not export the symbol.
+pub(crate) fn read_rdyn_version(library: &Library) -> Option<String> {
+    let version_sym: Symbol<RDynVersion> = unsafe { library.get(VERSION_RDYN_SYM_NAME) }.ok()?;
+    Some(version_sym().to_owned())
+}
@@ -0,0 +1,48 @@
+//! An example plugin that keeps its state when reloaded by the host with "reload_mod".
+//!
+//! Build it with `cargo build --example hot_reload_plugin` and copy the library from
+//! `target/debug/examples` into the host's mods directory, the host then reloads it with:
+//! ```ignore
+//! app.reload_mod("hot_reload_plugin::HotReloadPlugin")
+//! ```
+//! The counter continues from where the previous version of the plugin left off.
+use bevy::prelude::*;
+use bevy_rdyn_plugins::*;
+
+/// State owned by the plugin, handed over to the reloaded plugin.
+struct Counter(u32);
+
+#[derive(RDynPlugin)]
+pub struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Counter(0))
+            .add_plugin_system(self, Self::count);
+    }
+}
+
+impl HotReloadPlugin {
+    fn count(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+        println!("Counter: {}", counter.0);
+    }
+}
+
+/// Save the counter before the plugin is unloaded, removing it from the world
+/// as its type belongs to the library being unloaded.
+#[no_mangle]
+pub extern "Rust" fn _rdyn_snapshot(world: &mut World) -> Vec<u8> {
+    world
+        .remove_resource::<Counter>()
+        .map(|counter| counter.0.to_le_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Restore the counter saved by the previous version of the plugin, replacing the one inserted while building.
+#[no_mangle]
+pub extern "Rust" fn _rdyn_restore(world: &mut World, state: &[u8]) {
+    if let Ok(bytes) = state.try_into() {
+        world.insert_resource(Counter(u32::from_le_bytes(bytes)));
+    }
+}
@@ -0,0 +1,16 @@
+//! Prints the rdyn symbols exported by a plugin library, to diagnose why a plugin does not load.
+//!
+//! `cargo run --example inspect -- path/to/plugin.so`
+use bevy_rdyn_plugins::*;
+
+fn main() {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("Usage: inspect <plugin library>...");
+        std::process::exit(1);
+    }
+
+    for path in paths.iter() {
+        println!("{}", inspect_plugin(path));
+    }
+}
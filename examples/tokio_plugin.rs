@@ -0,0 +1,52 @@
+//! An example plugin that runs an async task on the host's tokio runtime instead of starting its own.
+//!
+//! Build it with `cargo build --example tokio_plugin --features tokio` and copy the library from
+//! `target/debug/examples` into the host's mods directory. The host, also built with the "tokio" feature
+//! and the same version of tokio, shares its runtime before loading mods with:
+//! ```ignore
+//! let runtime = tokio::runtime::Runtime::new().unwrap();
+//! app.insert_resource(HostContext::default().with_tokio_runtime(runtime.handle().clone()))
+//!     .load_mods("plugins");
+//! ```
+//! The runtime must be kept alive until the plugin is unloaded.
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_rdyn_plugins::*;
+
+/// The reply of the task, filled in once it has finished.
+#[derive(Default, Clone)]
+struct Reply(Arc<Mutex<Option<String>>>);
+
+#[derive(RDynPlugin)]
+#[rdyn_plugin(host_context)]
+pub struct TokioPlugin;
+
+impl Plugin for TokioPlugin {
+    fn build(&self, app: &mut App) {
+        let reply = Reply::default();
+        let task_reply = reply.clone();
+        let task = spawn_on_host_runtime(async move {
+            // Stands in for a network request, tokio's timers work as the task runs on the host's runtime.
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            *task_reply.0.lock().unwrap() = Some("Hello from the host's runtime!".to_string());
+        });
+        if task.is_none() {
+            warn!("The host did not share a tokio runtime, no task was spawned.");
+        }
+
+        app.insert_resource(reply)
+            .add_plugin_system(self, Self::print_reply);
+    }
+}
+
+impl TokioPlugin {
+    fn print_reply(reply: Res<Reply>) {
+        if let Some(message) = reply.0.lock().unwrap().take() {
+            println!("{}", message);
+        }
+    }
+}
@@ -9,7 +9,7 @@
 //! # Host example
 //! 
 //! ## main.rs
-//! ```
+//! ```ignore
 //! use bevy::prelude::*;
 //! use bevy_rdyn_plugins::*;
 //!
@@ -35,7 +35,7 @@
 //! dependencies such as [Bevy](https://bevyengine.org) itself for the sake of ergonomics when developing a plugin.
 //! 
 //! ## lib.rs
-//! ```
+//! ```ignore
 //! pub use bevy;
 //! pub use bevy_rdyn_plugins;
 //! ```
@@ -44,7 +44,7 @@
 //! with the API even easier. Don't forget to export this from your lib.rs also!
 //! 
 //! ## lib.rs
-//! ```
+//! ```ignore
 //! pub use bevy;
 //! pub use bevy_rdyn_plugins;
 //! 
@@ -52,7 +52,7 @@
 //! ```
 //! 
 //! ## prelude.rs
-//! ```
+//! ```ignore
 //! pub use bevy;
 //! pub use bevy_rdyn_plugins;
 //! 
@@ -63,7 +63,7 @@
 //! # Plugin example
 //! 
 //! ## Cargo.toml
-//! ```
+//! ```toml
 //! [lib]
 //! crate-type = ["dylib"]
 //! 
@@ -74,7 +74,7 @@
 //! The 'crate-type' specifies that the compiled binary should be built into a dynamic library.
 //! 
 //! ## lib.rs
-//! ```
+//! ```ignore
 //! use your_app::prelude::*;
 //! 
 //! #[derive(RDynPlugin)]
@@ -103,7 +103,7 @@
 //! resource from the application, just like any other plugin you write in Bevy would!
 //! 
 //! This is because you are writing a normal Bevy plugin! The only exception is the 
-//! ```
+//! ```text
 //! derive(RDynPlugin)
 //! ```
 //! neccessary to setup your file to be loaded by the host program. 
@@ -113,7 +113,7 @@
 //! Here is the same example but using [Bevy log](https://docs.rs/bevy/latest/bevy/log/index.html) instead.
 //! 
 //! ## lib.rs
-//! ```
+//! ```ignore
 //! use your_app::prelude::*;
 //! 
 //! #[derive(RDynPlugin)]